@@ -1,3 +1,4 @@
+import json
 from typing import Optional
 
 import click
@@ -9,8 +10,16 @@ from ..core import AbstractPlayer, LaskerMorris, TicTacToe
 init()  # Initialize colorama for Windows compatibility
 
 
-def _handle_game_result(winner: Optional[AbstractPlayer], visual: bool, game: any) -> None:
+def _print_json_result(result: dict) -> None:
+    """Single machine-readable line for tournament runners (see --json-result)."""
+    click.echo(f"RESULT {json.dumps(result)}")
+
+
+def _handle_game_result(winner: Optional[AbstractPlayer], visual: bool, game: any, json_result: bool = False) -> None:
     """Handle and display game result"""
+    if json_result:
+        _print_json_result(game.result_summary(winner))
+
     if winner:
         color_code = Fore.BLUE if hasattr(winner, 'get_color') and winner.get_color() == "blue" else Fore.YELLOW
         display_value = winner.get_color() if hasattr(winner, 'get_color') else winner.get_symbol()
@@ -43,7 +52,9 @@ def _handle_game_result(winner: Optional[AbstractPlayer], visual: bool, game: an
               help="Enable/disable logging")
 @click.option("--debug/--no-debug", "-d/-nd", default=LaskerConfig.DEFAULT_DEBUG,
               help="Enable/disable debug output")
-def start_game(player1, player2, visual, random_assignment, timeout, port, log, debug):
+@click.option("--json-result/--no-json-result", default=False,
+              help="Print the outcome as a single 'RESULT {json}' line")
+def start_game(player1, player2, visual, random_assignment, timeout, port, log, debug, json_result):
     """🎮 Start a new game of Lasker Morris!"""
     try:
         game = LaskerMorris(
@@ -58,10 +69,12 @@ def start_game(player1, player2, visual, random_assignment, timeout, port, log,
             print_board=debug
         )
         winner = game.run_game()
-        _handle_game_result(winner, visual, game)
+        _handle_game_result(winner, visual, game, json_result)
 
     except Exception as e:
         click.echo(f"\n{Fore.RED}Error: {e}{Style.RESET_ALL}")
+        if json_result:
+            _print_json_result({"version": 1, "winner": None, "termination": "error", "error": str(e)})
         raise click.Abort()
 
 
@@ -80,7 +93,9 @@ def start_game(player1, player2, visual, random_assignment, timeout, port, log,
               help="Enable/disable debug output")
 @click.option("--port", type=int, default=GameConfig.DEFAULT_WEB_PORT,
               help="Port for visualization webserver")
-def start_tictactoe(player, player2, visual, random_assignment, timeout, log, debug, port):
+@click.option("--json-result/--no-json-result", default=False,
+              help="Print the outcome as a single 'RESULT {json}' line")
+def start_tictactoe(player, player2, visual, random_assignment, timeout, log, debug, port, json_result):
     """🎮 Start a new game of TicTacToe!"""
     try:
         # Use same player command for both if player2 not provided
@@ -98,10 +113,12 @@ def start_tictactoe(player, player2, visual, random_assignment, timeout, log, de
         )
 
         winner = game.run_game()
-        _handle_game_result(winner, visual, game)
+        _handle_game_result(winner, visual, game, json_result)
 
     except Exception as e:
         click.echo(f"\n{Fore.RED}Error: {str(e)}{Style.RESET_ALL}")
+        if json_result:
+            _print_json_result({"version": 1, "winner": None, "termination": "error", "error": str(e)})
         raise click.Abort()
     finally:
         if "game" in locals():
@@ -54,6 +54,16 @@ class AbstractPlayer(ABC):
             return data
         return ""
 
+    def has_exited(self) -> bool:
+        # Give a process that just closed its output a moment to finish exiting
+        if self.process is None:
+            return False
+        try:
+            self.process.wait(timeout=0.5)
+            return True
+        except subprocess.TimeoutExpired:
+            return False
+
     def stop(self) -> None:
         sleep(0.25)
         if self.process:
@@ -91,6 +101,11 @@ class AbstractGame(ABC):
         self._player2 = player2
         self._current_player = player1
         self._is_game_over = False
+        self.termination: Optional[str] = None
+        self.moves_played = 0
+        self._move_times = {"player1": [], "player2": []}
+        self.history: list = []
+        self.rejected_move: Optional[dict] = None
 
     @property
     def current_player(self) -> AbstractPlayer:
@@ -115,6 +130,59 @@ class AbstractGame(ABC):
     def determine_winner(self) -> Optional[AbstractPlayer]:
         pass
 
+    def slot(self, player: AbstractPlayer) -> str:
+        """'player1' or 'player2', following the order of the command line."""
+        return "player1" if player is self._player1 else "player2"
+
+    def record_move_time(self, player: AbstractPlayer, seconds: float) -> None:
+        self._move_times[self.slot(player)].append(seconds)
+
+    def _move_record(self, player: AbstractPlayer, move: str) -> dict:
+        times = self._move_times[self.slot(player)]
+        return {"player": self.slot(player), "move": move.strip(), "time": round(times[-1], 3) if times else None}
+
+    def record_move(self, player: AbstractPlayer, move: str) -> None:
+        """Add a legal move to the game's history, with the time it took."""
+        self.history.append(self._move_record(player, move))
+
+    def record_rejected_move(self, player: AbstractPlayer, move: str) -> None:
+        """Remember the move that lost the game by forfeit."""
+        self.rejected_move = self._move_record(player, move)
+
+    def result_summary(self, winner: Optional[AbstractPlayer]) -> dict:
+        """Machine-readable outcome of a finished game, for --json-result."""
+        timings = {
+            slot: {
+                "total": round(sum(times), 3),
+                "max": round(max(times, default=0.0), 3),
+                "moves": len(times),
+            }
+            for slot, times in self._move_times.items()
+        }
+        summary = {
+            "version": 1,
+            "winner": self.slot(winner) if winner else None,
+            "termination": self.termination or ("draw" if winner is None else "unknown"),
+            "moves": self.moves_played,
+            "colors": {self.slot(p): p.side() for p in (self._player1, self._player2)},
+            "timings": timings,
+            "history": self.history,
+        }
+        if self.rejected_move is not None:
+            summary["rejected_move"] = self.rejected_move
+        return summary
+
+    @staticmethod
+    def forfeit_reason(player: AbstractPlayer, move: Optional[str], well_formed: bool) -> str:
+        """Why `player` lost the game by failing to make a legal move."""
+        if move is None:
+            return "timeout"
+        if player.has_exited():
+            return "crash"
+        if not well_formed:
+            return "protocol_violation"
+        return "invalid_move"
+
 
 class WebGame(ABC):
     """Abstract base class for web-enabled games"""
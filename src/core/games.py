@@ -1,4 +1,5 @@
 import random
+import time
 from concurrent.futures import ThreadPoolExecutor, TimeoutError
 from typing import Optional, Tuple
 
@@ -165,6 +166,7 @@ class TicTacToe(AbstractGame):
         return None
 
     def _get_move_with_timeout(self) -> Optional[str]:
+        start = time.monotonic()
         with ThreadPoolExecutor(max_workers=1) as executor:
             try:
                 future = executor.submit(self.current_player.read)
@@ -175,6 +177,8 @@ class TicTacToe(AbstractGame):
                     f"took too long to respond{Style.RESET_ALL}"
                 )
                 return None
+            finally:
+                self.record_move_time(self.current_player, time.monotonic() - start)
 
     def run_game(self) -> Optional[TicTacToePlayer]:
         """Main game loop."""
@@ -186,6 +190,12 @@ class TicTacToe(AbstractGame):
                 self._is_game_over = True
                 winner = self._player2 if self.current_player == self._player1 else self._player1
                 reason = "Time out!" if move is None else f"Invalid move {move}!"
+                well_formed = move is not None and self._validate_move_format(move)[0]
+                forfeit = self.forfeit_reason(self.current_player, move, well_formed)
+                if move:
+                    self.record_rejected_move(self.current_player, move)
+                click.echo(f"Forfeit: {self.current_player.get_symbol()} ({forfeit})")
+                self.termination = forfeit
                 message = f"END: {winner.get_symbol()} WINS! {self.current_player.get_symbol()} LOSES! {reason}"
 
                 if self.visual and self.web:
@@ -194,6 +204,9 @@ class TicTacToe(AbstractGame):
                 self._cleanup_game()
                 return winner
 
+            self.moves_played += 1
+            self.record_move(self.current_player, move)
+
             # Write move to other player
             other_player = self._player2 if self.current_player == self._player1 else self._player1
             other_player.write(move)
@@ -202,12 +215,14 @@ class TicTacToe(AbstractGame):
             winner = self.determine_winner()
             if winner is not None:
                 message = f"END: {winner.get_symbol()} WINS! {other_player.get_symbol()} LOSES! Three in a row!"
+                self.termination = "three_in_a_row"
                 if self.visual and self.web:
                     self.web.end_message = message
                 self._cleanup_game()
                 return winner
             elif self._is_board_full():
                 message = "Draw!"
+                self.termination = "board_full"
                 if self.visual and self.web:
                     self.web.end_message = message
                 self._cleanup_game()
@@ -591,6 +606,7 @@ class LaskerMorris(AbstractGame):
         # Check for draw condition
         if self.moves_without_taking >= 20:
             self._is_game_over = True
+            self.termination = "no_capture_limit"
             message = "Draw!"
             click.echo(message)
             if self.visual and self.web:
@@ -608,6 +624,7 @@ class LaskerMorris(AbstractGame):
         return None
 
     def _get_move_with_timeout(self) -> Optional[str]:
+        start = time.monotonic()
         with ThreadPoolExecutor(max_workers=1) as executor:
             try:
                 future = executor.submit(self._current_player.read)
@@ -615,6 +632,8 @@ class LaskerMorris(AbstractGame):
             except TimeoutError:
                 click.echo(f"\n{Fore.RED}Move timeout: {self._current_player.get_color()}{Style.RESET_ALL}")
                 return None
+            finally:
+                self.record_move_time(self._current_player, time.monotonic() - start)
 
     def run_game(self) -> Optional[LaskerPlayer]:
         while not self.is_game_over:
@@ -625,6 +644,7 @@ class LaskerMorris(AbstractGame):
                 winner_color = winner.get_color()
                 loser_color = self._current_player.get_color()
                 message = f"END: {winner_color} WINS! {loser_color} LOSES! No valid moves available!"
+                self.termination = "no_valid_moves"
                 if self.visual and self.web:
                     self.web.end_message = message
                 self._cleanup_game()
@@ -640,6 +660,12 @@ class LaskerMorris(AbstractGame):
 
                 # Error message based on failure reason
                 reason = "Time out!" if not move else f"Invalid move {move}!"
+                well_formed = bool(move) and len(move.split()) == 3
+                forfeit = self.forfeit_reason(self._current_player, move, well_formed)
+                if move:
+                    self.record_rejected_move(self._current_player, move)
+                click.echo(f"Forfeit: {loser_color} ({forfeit})")
+                self.termination = forfeit
                 message = f"END: {winner_color} WINS! {loser_color} LOSES! {reason}"
 
                 if self.visual and self.web:
@@ -647,6 +673,9 @@ class LaskerMorris(AbstractGame):
                 self._cleanup_game()
                 return winner
 
+            self.moves_played += 1
+            self.record_move(self._current_player, move)
+
             # Send move to other player
             other_player = self._player2 if self._current_player == self._player1 else self._player1
             other_player.write(move)
@@ -657,6 +686,7 @@ class LaskerMorris(AbstractGame):
                 winner_color = winner.get_color()
                 loser_color = other_player.get_color()
                 message = f"END: {winner_color} WINS! {loser_color} LOSES! Ran out of pieces!"
+                self.termination = "fewer_than_three_pieces"
                 if self.visual and self.web:
                     self.web.end_message = message
                 self._cleanup_game()
@@ -28,6 +28,9 @@ class LaskerPlayer(AbstractPlayer):
     def get_color(self) -> str:
         return self.color.value
 
+    def side(self) -> str:
+        return self.color.value
+
     def is_blue(self) -> bool:
         return self.color == PlayerColor.BLUE
 
@@ -48,6 +51,9 @@ class TicTacToePlayer(AbstractPlayer):
     def get_symbol(self) -> str:
         return self.symbol.value
 
+    def side(self) -> str:
+        return self.symbol.value.lower()
+
     def is_x(self) -> bool:
         return self.symbol == PlayerSymbol.X
 
@@ -1,3 +1,4 @@
+import subprocess
 from typing import Any, Optional, Tuple
 from unittest.mock import Mock, patch
 
@@ -172,3 +173,27 @@ class TestAbstractGame:
         game._is_game_over = True
         assert game.is_game_over
         assert game.determine_winner() == game._player1
+
+    @patch("subprocess.Popen")
+    def test_forfeit_reason(self, mock_popen: Mock) -> None:
+        """Test that a failed move is blamed on the right cause"""
+        player = MockPlayer()
+        player.start()
+        mock_popen.return_value.wait.side_effect = subprocess.TimeoutExpired("player", 0.5)
+
+        assert AbstractGame.forfeit_reason(player, None, False) == "timeout"
+        assert AbstractGame.forfeit_reason(player, "zz", False) == "protocol_violation"
+        assert AbstractGame.forfeit_reason(player, "a1", True) == "invalid_move"
+
+        mock_popen.return_value.wait.side_effect = None
+        assert AbstractGame.forfeit_reason(player, "", False) == "crash"
+
+    def test_move_history(self, game: MockGame) -> None:
+        """Test that moves are recorded with the time they took"""
+        game.record_move_time(game._player1, 0.25)
+        game.record_move(game._player1, "b2\n")
+        game.record_move_time(game._player2, 0.5)
+        game.record_rejected_move(game._player2, "b2")
+
+        assert game.history == [{"player": "player1", "move": "b2", "time": 0.25}]
+        assert game.rejected_move == {"player": "player2", "move": "b2", "time": 0.5}
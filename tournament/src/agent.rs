@@ -0,0 +1,190 @@
+use log::debug;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Why an agent did not produce a move.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReadError {
+    Timeout,
+    /// The agent closed its output, usually because it exited.
+    Closed,
+}
+
+/// An agent process driven over stdin/stdout, one message per line.
+pub struct Agent {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<String>,
+}
+
+impl Agent {
+    pub fn spawn(command: &str) -> Result<Self, String> {
+        let words = split_command(command)?;
+        let (program, args) = words.split_first().ok_or("Empty agent command")?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Could not start '{}': {}", program, e))?;
+
+        // A reader thread turns the blocking pipe into something we can wait on with a timeout
+        let stdout = child.stdout.take().ok_or("Agent stdout was not captured")?;
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Agent { stdin: child.stdin.take(), child, lines })
+    }
+
+    /// Send one line. A failed write is ignored; the agent will then fail to move.
+    pub fn write(&mut self, message: &str) {
+        if let Some(stdin) = &mut self.stdin {
+            if writeln!(stdin, "{}", message).and_then(|_| stdin.flush()).is_err() {
+                debug!("Could not write '{}' to agent", message);
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for the next line, returning it trimmed along
+    /// with how long the agent took.
+    pub fn read(&mut self, timeout: Duration) -> (Result<String, ReadError>, Duration) {
+        let start = Instant::now();
+        let line = match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(line.trim().to_string()),
+            Err(RecvTimeoutError::Timeout) => Err(ReadError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(ReadError::Closed),
+        };
+        (line, start.elapsed())
+    }
+
+    /// Whether the process has exited, giving it a moment to finish if it
+    /// just closed its output.
+    pub fn has_exited(&mut self) -> bool {
+        let deadline = Instant::now() + Duration::from_millis(500);
+        loop {
+            match self.child.try_wait() {
+                Ok(Some(_)) | Err(_) => return true,
+                Ok(None) if Instant::now() >= deadline => return false,
+                Ok(None) => thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    }
+
+    pub fn close_input(&mut self) {
+        self.stdin = None;
+    }
+
+    /// Close stdin so the agent can exit on its own, then kill it if it has not.
+    pub fn stop(&mut self) {
+        self.close_input();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for Agent {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Split a command line into words the way a POSIX shell would, honouring
+/// single and double quotes and backslash escapes (like Python's `shlex.split`).
+pub fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unterminated quote in: {}", command)),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(format!("Unterminated quote in: {}", command)),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unterminated quote in: {}", command)),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_like_a_shell() {
+        assert_eq!(split_command("python3 player.py").unwrap(), vec!["python3", "player.py"]);
+        assert_eq!(
+            split_command(r#"java -cp "my dir" 'Main Class' a\ b"#).unwrap(),
+            vec!["java", "-cp", "my dir", "Main Class", "a b"]
+        );
+        assert_eq!(split_command(r#"echo "" x"#).unwrap(), vec!["echo", "", "x"]);
+        assert!(split_command("python3 'oops").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+/// One series to be played in a bracket. `player1` moves first in the opening game.
+#[derive(Debug, Clone)]
+pub struct BracketMatch {
+    pub label: String,
+    pub player1: String,
+    pub player2: String,
+}
+
+type Slot = Option<String>;
+
+/// Bracket positions for a field of `size` (a power of two), as 0-based
+/// seeds, arranged so the top two seeds can only meet in the final:
+/// 8 -> [0, 7, 3, 4, 1, 6, 2, 5].
+pub fn seed_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![0];
+    while positions.len() < size {
+        let round_size = positions.len() * 2;
+        positions = positions.iter()
+            .flat_map(|&seed| [seed, round_size - 1 - seed])
+            .collect();
+    }
+    positions
+}
+
+/// First round slots for `seeds` (best first), padded with byes up to the
+/// next power of two. Byes land next to the top seeds.
+fn first_round(seeds: &[String]) -> Vec<Slot> {
+    let size = seeds.len().next_power_of_two().max(2);
+    seed_positions(size).into_iter()
+        .map(|seed| seeds.get(seed).cloned())
+        .collect()
+}
+
+fn round_name(round: usize, total_rounds: usize) -> String {
+    match total_rounds - round {
+        0 => "Final".to_string(),
+        1 => "Semifinal".to_string(),
+        2 => "Quarterfinal".to_string(),
+        _ => format!("Round of {}", 1 << (total_rounds - round + 1)),
+    }
+}
+
+/// Play every pair of slots that has two players; a lone player gets a bye.
+/// Returns (winners, losers), one slot per pair.
+fn play_pairs<E, F>(
+    pairs: Vec<(Slot, Slot)>,
+    label: impl Fn(usize) -> String,
+    play: &mut F,
+) -> Result<(Vec<Slot>, Vec<Slot>), E>
+where
+    F: FnMut(Vec<BracketMatch>) -> Result<Vec<String>, E>,
+{
+    let mut winners = vec![None; pairs.len()];
+    let mut losers = vec![None; pairs.len()];
+    let mut matches = Vec::new();
+    let mut positions = Vec::new();
+
+    for (index, pair) in pairs.into_iter().enumerate() {
+        match pair {
+            (Some(player1), Some(player2)) => {
+                positions.push(index);
+                matches.push(BracketMatch { label: label(index + 1), player1, player2 });
+            }
+            (Some(player), None) | (None, Some(player)) => winners[index] = Some(player),
+            (None, None) => {}
+        }
+    }
+
+    if !matches.is_empty() {
+        let results = play(matches.clone())?;
+        for ((index, bracket_match), winner) in positions.into_iter().zip(matches).zip(results) {
+            let loser = if winner == bracket_match.player1 {
+                bracket_match.player2
+            } else {
+                bracket_match.player1
+            };
+            winners[index] = Some(winner);
+            losers[index] = Some(loser);
+        }
+    }
+
+    Ok((winners, losers))
+}
+
+fn into_pairs(slots: Vec<Slot>) -> Vec<(Slot, Slot)> {
+    let mut pairs = Vec::new();
+    let mut slots = slots.into_iter();
+    while let Some(first) = slots.next() {
+        pairs.push((first, slots.next().flatten()));
+    }
+    pairs
+}
+
+/// Final ranking: the champion, then everyone else by how late they were
+/// knocked out. Players knocked out in the same round are ordered by seed.
+fn ranking(champion: Option<String>, eliminated: Vec<Vec<String>>, seeds: &[String]) -> Vec<String> {
+    let seed_of = |player: &String| seeds.iter().position(|seed| seed == player).unwrap_or(usize::MAX);
+
+    let mut ranked: Vec<String> = champion.into_iter().collect();
+    for mut tier in eliminated.into_iter().rev() {
+        tier.sort_by_key(seed_of);
+        ranked.extend(tier);
+    }
+    ranked
+}
+
+/// Run a single elimination bracket over `seeds` (best first).
+///
+/// `play` receives every series of one bracket round and returns the winner
+/// of each, in order. Returns the final ranking of all entrants.
+pub fn single_elimination<E, F>(seeds: &[String], mut play: F) -> Result<Vec<String>, E>
+where
+    F: FnMut(Vec<BracketMatch>) -> Result<Vec<String>, E>,
+{
+    let mut slots = first_round(seeds);
+    let total_rounds = slots.len().trailing_zeros() as usize;
+    let mut eliminated = Vec::new();
+
+    for round in 1..=total_rounds {
+        let name = round_name(round, total_rounds);
+        let label = |index: usize| {
+            if round == total_rounds { name.clone() } else { format!("{} {}", name, index) }
+        };
+
+        let (winners, losers) = play_pairs(into_pairs(slots), label, &mut play)?;
+        eliminated.push(losers.into_iter().flatten().collect());
+        slots = winners;
+    }
+
+    Ok(ranking(slots.into_iter().flatten().next(), eliminated, seeds))
+}
+
+/// Run a double elimination bracket over `seeds` (best first).
+///
+/// Losers of the winners bracket drop into the losers bracket; a second loss
+/// eliminates. The losers bracket champion must beat the winners bracket
+/// champion twice in the grand final.
+pub fn double_elimination<E, F>(seeds: &[String], mut play: F) -> Result<Vec<String>, E>
+where
+    F: FnMut(Vec<BracketMatch>) -> Result<Vec<String>, E>,
+{
+    let mut winners_slots = first_round(seeds);
+    let total_rounds = winners_slots.len().trailing_zeros() as usize;
+    let mut losers_slots: Vec<Slot> = Vec::new();
+    let mut losers_round = 0;
+    let mut eliminated: Vec<Vec<String>> = Vec::new();
+
+    for round in 1..=total_rounds {
+        let label = |index: usize| format!("Winners Round {} Match {}", round, index);
+        let (winners, mut dropped) = play_pairs(into_pairs(winners_slots), label, &mut play)?;
+        winners_slots = winners;
+
+        let pairs = if round == 1 {
+            into_pairs(dropped)
+        } else {
+            // Flip every other drop-in so players avoid an immediate rematch
+            if round.is_multiple_of(2) {
+                dropped.reverse();
+            }
+            losers_slots.into_iter().zip(dropped).collect()
+        };
+        losers_round += 1;
+        let label = |index: usize| format!("Losers Round {} Match {}", losers_round, index);
+        let (survivors, knocked_out) = play_pairs(pairs, label, &mut play)?;
+        eliminated.push(knocked_out.into_iter().flatten().collect());
+        losers_slots = survivors;
+
+        // Survivors play each other until the bracket matches the next drop-in
+        if round > 1 && losers_slots.len() > 1 {
+            losers_round += 1;
+            let label = |index: usize| format!("Losers Round {} Match {}", losers_round, index);
+            let (survivors, knocked_out) = play_pairs(into_pairs(losers_slots), label, &mut play)?;
+            eliminated.push(knocked_out.into_iter().flatten().collect());
+            losers_slots = survivors;
+        }
+    }
+
+    let winners_champion = winners_slots.into_iter().flatten().next();
+    let losers_champion = losers_slots.into_iter().flatten().next();
+
+    let champion = match (winners_champion, losers_champion) {
+        (Some(top), Some(challenger)) => {
+            let final_label = |_: usize| "Grand Final".to_string();
+            let (winner, loser) = play_pairs(vec![(Some(top.clone()), Some(challenger.clone()))], final_label, &mut play)?;
+            let (mut champion, mut runner_up) = (winner[0].clone(), loser[0].clone());
+
+            if champion.as_ref() == Some(&challenger) {
+                // First loss for the winners bracket champion: play it again
+                let reset_label = |_: usize| "Grand Final Reset".to_string();
+                let (winner, loser) = play_pairs(vec![(Some(top), Some(challenger))], reset_label, &mut play)?;
+                champion = winner[0].clone();
+                runner_up = loser[0].clone();
+            }
+
+            eliminated.push(runner_up.into_iter().collect());
+            champion
+        }
+        (champion, None) | (None, champion) => champion,
+    };
+
+    Ok(ranking(champion, eliminated, seeds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(n: usize) -> Vec<String> {
+        (1..=n).map(|i| format!("p{}", i)).collect()
+    }
+
+    /// The better seed (lower number) always wins.
+    fn favourite(matches: Vec<BracketMatch>) -> Result<Vec<String>, ()> {
+        Ok(matches.into_iter()
+            .map(|m| {
+                let seed = |p: &str| p[1..].parse::<usize>().unwrap();
+                if seed(&m.player1) < seed(&m.player2) { m.player1 } else { m.player2 }
+            })
+            .collect())
+    }
+
+    #[test]
+    fn seed_positions_keep_top_seeds_apart() {
+        assert_eq!(seed_positions(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+    }
+
+    #[test]
+    fn single_elimination_gives_byes_to_top_seeds() {
+        let mut first_round = Vec::new();
+        let ranking = single_elimination(&players(6), |matches| {
+            if first_round.is_empty() {
+                first_round = matches.iter().map(|m| (m.player1.clone(), m.player2.clone())).collect();
+            }
+            favourite(matches)
+        }).unwrap();
+
+        assert_eq!(first_round, vec![("p4".into(), "p5".into()), ("p3".into(), "p6".into())]);
+        assert_eq!(ranking, players(6));
+    }
+
+    #[test]
+    fn double_elimination_ranks_everyone() {
+        let ranking = double_elimination(&players(8), favourite).unwrap();
+        assert_eq!(ranking.len(), 8);
+        assert_eq!(&ranking[..3], &["p1", "p2", "p3"]);
+    }
+
+    #[test]
+    fn grand_final_reset_when_winners_champion_loses() {
+        let mut labels = Vec::new();
+        let ranking = double_elimination(&players(2), |matches| {
+            labels.extend(matches.iter().map(|m| m.label.clone()));
+            // p2 wins everything except the very first game
+            Ok::<_, ()>(matches.into_iter()
+                .map(|m| if m.label.starts_with("Winners") { m.player1 } else { "p2".to_string() })
+                .collect())
+        }).unwrap();
+
+        assert_eq!(labels, vec!["Winners Round 1 Match 1", "Grand Final", "Grand Final Reset"]);
+        assert_eq!(ranking, vec!["p2", "p1"]);
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::stage::StageConfig;
+use crate::MatchResult;
+
+/// Everything needed to pick an interrupted tournament back up.
+///
+/// The schedule is fully determined by the config, the RNG seed and the
+/// results so far, so a resumed run replays the saved results in order and
+/// only plays the games that are missing. The stage and groups the run had
+/// reached are saved too, to catch a config that no longer draws the same
+/// schedule.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub seed: u64,
+    pub stage_index: usize,
+    pub round: String,
+    pub groups: BTreeMap<String, Vec<String>>,
+    pub results: Vec<MatchResult>,
+}
+
+/// Where the interrupted run was after its last saved game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    /// Number of games played up to this point.
+    pub games: usize,
+    pub stage_index: usize,
+    pub round: String,
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+impl Checkpoint {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Err(format!("No checkpoint found at {}; start the tournament without --resume", path).into());
+        }
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        write_atomically(path, self)
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            games: self.results.len(),
+            stage_index: self.stage_index,
+            round: self.round.clone(),
+            groups: self.groups.clone(),
+        }
+    }
+
+    /// The checkpoint must have stopped in a stage the config still has, under the same name.
+    pub fn check_stages(&self, stages: &[StageConfig]) -> Result<(), String> {
+        match stages.get(self.stage_index) {
+            Some(stage) if stage.name == self.round => Ok(()),
+            Some(stage) => Err(format!(
+                "The checkpoint stopped in stage {} ({}), but that stage is now {}. \
+                 Was the config changed since the tournament started?",
+                self.stage_index + 1, self.round, stage.name
+            )),
+            None => Err(format!(
+                "The checkpoint stopped in stage {} ({}), but the config has only {} stage(s). \
+                 Was the config changed since the tournament started?",
+                self.stage_index + 1, self.round, stages.len()
+            )),
+        }
+    }
+}
+
+/// Write `value` as JSON to `path`. It goes to a temporary file first and is
+/// renamed into place, so an interruption mid-write never leaves a truncated
+/// file behind.
+pub fn write_atomically<T: Serialize>(path: &str, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let temp_path = format!("{}.tmp", path);
+    fs::write(&temp_path, serde_json::to_string(value)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+impl Position {
+    /// Compare with where a resumed run is after playing as many games,
+    /// `unreplayed` of the saved games never having come up in its schedule.
+    pub fn check(
+        &self,
+        stage_index: usize,
+        round: &str,
+        groups: &BTreeMap<String, Vec<String>>,
+        unreplayed: usize,
+    ) -> Result<(), String> {
+        if unreplayed > 0 {
+            return Err(format!(
+                "The checkpoint does not match the schedule: {} of its {} games never came up when replaying it. \
+                 Was the config changed since the tournament started?",
+                unreplayed, self.games
+            ));
+        }
+        if stage_index == self.stage_index && round == self.round && groups == &self.groups {
+            return Ok(());
+        }
+        Err(format!(
+            "The checkpoint does not match the schedule: after {} games it was in {} with {}, \
+             but replaying them reaches {} with {}. Was the config changed since the tournament started?",
+            self.games, self.round, describe(&self.groups), round, describe(groups)
+        ))
+    }
+}
+
+fn describe(groups: &BTreeMap<String, Vec<String>>) -> String {
+    if groups.is_empty() {
+        return "no groups".to_string();
+    }
+    groups.iter()
+        .map(|(group, players)| format!("{} ({})", group, players.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stage::default_stages;
+
+    fn groups(groups: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        groups.iter()
+            .map(|(group, players)| (group.to_string(), players.iter().map(|player| player.to_string()).collect()))
+            .collect()
+    }
+
+    fn checkpoint() -> Checkpoint {
+        Checkpoint {
+            seed: 42,
+            stage_index: 1,
+            round: "Second Round".to_string(),
+            groups: groups(&[("Group A", &["a", "b"]), ("Group B", &["c", "d"])]),
+            results: vec![MatchResult::test("a", "c", Some("a")), MatchResult::test("b", "d", None)],
+        }
+    }
+
+    #[test]
+    fn saves_and_loads_the_same_state() {
+        let path = std::env::temp_dir().join(format!("checkpoint_test_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        checkpoint().save(path).unwrap();
+        let loaded = Checkpoint::load(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.seed, 42);
+        assert_eq!(loaded.position(), checkpoint().position());
+        assert_eq!(serde_json::to_string(&loaded.results).unwrap(), serde_json::to_string(&checkpoint().results).unwrap());
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+    }
+
+    #[test]
+    fn rejects_a_checkpoint_from_another_schedule() {
+        let checkpoint = checkpoint();
+        assert!(checkpoint.check_stages(&default_stages()).is_ok());
+        let error = checkpoint.check_stages(&default_stages()[..1]).unwrap_err();
+        assert!(error.contains("the config has only 1 stage(s)"), "{}", error);
+        let mut renamed = default_stages();
+        renamed[1].name = "Quarterfinals".to_string();
+        assert!(checkpoint.check_stages(&renamed).unwrap_err().contains("that stage is now Quarterfinals"));
+
+        let position = checkpoint.position();
+        assert!(position.check(1, "Second Round", &checkpoint.groups, 0).is_ok());
+        assert!(position.check(1, "Second Round", &checkpoint.groups, 1).unwrap_err().contains("1 of its 2 games never came up"));
+        let redrawn = groups(&[("Group A", &["a", "c"]), ("Group B", &["b", "d"])]);
+        let error = position.check(1, "Second Round", &redrawn, 0).unwrap_err();
+        assert!(error.contains("after 2 games it was in Second Round with Group A (a, b), Group B (c, d)"), "{}", error);
+        assert!(position.check(2, "Third Round", &checkpoint.groups, 0).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use log::LevelFilter;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Play the tournament (the default).
+    Run,
+    /// Check the configuration and exit without playing.
+    Validate,
+    /// Print the moves of a saved game transcript.
+    Replay { transcript: String },
+    /// Print the leaderboard of the configured ladder.
+    Ladder,
+}
+
+/// Command line options for the tournament runner.
+#[derive(Debug)]
+pub struct Options {
+    pub command: Command,
+    pub config_path: String,
+    pub log_level: LevelFilter,
+    pub resume: bool,
+    pub random_seed: Option<u64>,
+    /// Number of games to run at the same time; overrides `settings.parallelism`.
+    pub jobs: Option<usize>,
+    /// Do not check that agent programs exist.
+    pub skip_validation: bool,
+    /// Move to jump to when replaying (0 is the empty board).
+    pub replay_move: Option<usize>,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut command = Command::Run;
+    let mut config_path = None;
+    let mut quiet = false;
+    let mut debug = false;
+    let mut no_log = false;
+    let mut resume = false;
+    let mut random_seed = None;
+    let mut jobs = None;
+    let mut skip_validation = false;
+    let mut replay = false;
+    let mut replay_move = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--quiet" | "-q" => quiet = true,
+            "--debug" | "-d" => debug = true,
+            "--no-log" => no_log = true,
+            "--resume" => resume = true,
+            "--skip-validation" => skip_validation = true,
+            "--random-seed" => {
+                let value = iter.next().ok_or("--random-seed needs a value")?;
+                let seed = value.parse()
+                    .map_err(|_| format!("--random-seed must be a non-negative integer, got '{}'", value))?;
+                random_seed = Some(seed);
+            }
+            "--jobs" | "-j" => {
+                let value = iter.next().ok_or("--jobs needs a value")?;
+                let count = value.parse().ok().filter(|&count: &usize| count > 0)
+                    .ok_or_else(|| format!("--jobs must be a positive integer, got '{}'", value))?;
+                jobs = Some(count);
+            }
+            "--move" | "-m" => {
+                let value = iter.next().ok_or("--move needs a value")?;
+                let number = value.parse()
+                    .map_err(|_| format!("--move must be a non-negative integer, got '{}'", value))?;
+                replay_move = Some(number);
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            "validate" if command == Command::Run && !replay && config_path.is_none() => command = Command::Validate,
+            "replay" if command == Command::Run && !replay && config_path.is_none() => replay = true,
+            "ladder" if command == Command::Run && !replay && config_path.is_none() => command = Command::Ladder,
+            path => config_path = Some(path.to_string()),
+        }
+    }
+
+    if replay {
+        let transcript = config_path.take().ok_or("replay needs the path of a transcript file")?;
+        command = Command::Replay { transcript };
+    } else if replay_move.is_some() {
+        return Err("--move only applies to replay".to_string());
+    }
+
+    // Check for logging level
+    let log_level = if quiet {
+        LevelFilter::Error // Only show errors
+    } else if debug {
+        LevelFilter::Debug // Show debug and above
+    } else if no_log {
+        LevelFilter::Off // Turn off logging completely
+    } else {
+        LevelFilter::Info // Default: Show info and above
+    };
+
+    Ok(Options {
+        command,
+        config_path: config_path.unwrap_or_else(|| "tournament.toml".to_string()),
+        log_level,
+        resume,
+        random_seed,
+        jobs,
+        skip_validation,
+        replay_move,
+    })
+}
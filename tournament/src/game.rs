@@ -0,0 +1,127 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::laskermorris::LaskerMorris;
+use crate::tictactoe::TicTacToe;
+
+/// Every game the runner can host. Each game lives in its own module and
+/// implements [`Game`]; listing it here makes it available as `game = "..."`
+/// in the config, through the Python referee and the native engine alike.
+pub static GAMES: &[&dyn Game] = &[&TicTacToe, &LaskerMorris];
+
+/// How a game ended: the winning player slot (`None` for a draw) and the
+/// termination reported in the results, e.g. "three_in_a_row".
+pub type Outcome = (Option<usize>, &'static str);
+
+/// A move the rules do not allow.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IllegalMove {
+    /// Whether the move had the right shape (an invalid move rather than a
+    /// protocol violation).
+    pub well_formed: bool,
+    pub reason: String,
+}
+
+pub trait Game: Debug + Sync {
+    /// Name used for `game` in the config and as the referee subcommand.
+    fn name(&self) -> &'static str;
+
+    /// Other spellings accepted for `game`.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Referee option naming the first player's command.
+    fn player1_flag(&self) -> &'static str {
+        "--player1"
+    }
+
+    /// Color of each player slot. Player 1 moves first.
+    fn colors(&self) -> [&'static str; 2] {
+        ["blue", "orange"]
+    }
+
+    /// Other names the referee may use for each color in its output.
+    fn color_aliases(&self) -> [&'static [&'static str]; 2] {
+        [&[], &[]]
+    }
+
+    /// First line sent to each player.
+    fn initial_messages(&self) -> [String; 2] {
+        self.colors().map(str::to_string)
+    }
+
+    /// Line sent to both players once the game is over, if any.
+    fn end_message(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Extra time allowed per move on top of the timeout.
+    fn timeout_grace(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// A fresh game for the native engine.
+    fn new_state(&self) -> Box<dyn GameState>;
+}
+
+/// A game in progress, as driven by the native engine.
+pub trait GameState {
+    /// Slot of the player to move.
+    fn turn(&self) -> usize;
+
+    /// Outcome decided before the player to move is even asked, e.g. when
+    /// they have no legal move.
+    fn before_move(&self) -> Option<Outcome> {
+        None
+    }
+
+    /// Check `text` against the rules and play it for the player to move.
+    /// Returns the outcome if this move ends the game.
+    fn play(&mut self, text: &str) -> Result<Option<Outcome>, IllegalMove>;
+
+    /// The board drawn in plain text, for transcripts and replays.
+    fn board(&self) -> String;
+}
+
+/// Look up a game by its name or one of its aliases.
+pub fn find(name: &str) -> Option<&'static dyn Game> {
+    GAMES.iter().copied().find(|game| game.name() == name || game.aliases().contains(&name))
+}
+
+/// Slot of the player the referee calls `color`, if it names one.
+pub fn slot_for_color(game: &dyn Game, color: &str) -> Option<usize> {
+    let color = color.to_ascii_lowercase();
+    (0..2).find(|&slot| game.colors()[slot] == color || game.color_aliases()[slot].contains(&color.as_str()))
+}
+
+/// "tictactoe or laskermorris", for error messages.
+pub fn names() -> String {
+    let names: Vec<&str> = GAMES.iter().map(|game| game.name()).collect();
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games_are_found_by_name_and_alias() {
+        assert_eq!(find("tictactoe").map(|game| game.name()), Some("tictactoe"));
+        assert_eq!(find("lasker-morris").map(|game| game.name()), Some("laskermorris"));
+        assert!(find("chess").is_none());
+        assert_eq!(names(), "tictactoe or laskermorris");
+    }
+
+    #[test]
+    fn colors_and_aliases_name_slots() {
+        let tictactoe = find("tictactoe").unwrap();
+        assert_eq!(slot_for_color(tictactoe, "BLUE"), Some(0));
+        assert_eq!(slot_for_color(tictactoe, "O"), Some(1));
+        assert_eq!(slot_for_color(find("laskermorris").unwrap(), "x"), None);
+    }
+}
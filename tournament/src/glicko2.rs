@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::rating::{first_player_score, starting_table, Rating};
+use crate::MatchResult;
+
+/// Converts between the Glicko scale (ratings around 1500) and Glicko-2's
+/// internal one.
+const SCALE: f64 = 173.7178;
+/// Volatility every agent starts with.
+const INITIAL_VOLATILITY: f64 = 0.06;
+const CONVERGENCE: f64 = 0.000_001;
+
+/// A rating on Glicko-2's internal scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Estimate {
+    mu: f64,
+    phi: f64,
+    sigma: f64,
+}
+
+/// Glicko-2 ratings from `games`. Each round of the tournament (a run of
+/// consecutive games with the same round name) is one rating period: every
+/// game in it is rated against the opponents' ratings from before the round.
+/// Agents who sit a period out become a little less certain.
+pub fn rate(players: &[String], games: &[&MatchResult], initial_rating: f64, initial_deviation: f64, tau: f64) -> Vec<Rating> {
+    let start = Estimate { mu: 0.0, phi: initial_deviation / SCALE, sigma: INITIAL_VOLATILITY };
+    let mut ratings = starting_table(players, games, start);
+
+    for period in games.chunk_by(|a, b| a.round == b.round) {
+        // (opponent before the period, score) for every game of every player
+        let mut results: HashMap<&str, Vec<(Estimate, f64)>> = HashMap::new();
+        for game in period {
+            let score = first_player_score(game);
+            let (first, second) = (ratings[&game.player1].0, ratings[&game.player2].0);
+            results.entry(&game.player1).or_default().push((second, score));
+            results.entry(&game.player2).or_default().push((first, 1.0 - score));
+        }
+
+        for (player, (estimate, played)) in ratings.iter_mut() {
+            match results.get(player.as_str()) {
+                Some(results) => {
+                    *estimate = update(*estimate, results, tau);
+                    *played += results.len() as u32;
+                }
+                None => estimate.phi = estimate.phi.hypot(estimate.sigma),
+            }
+        }
+    }
+
+    ratings.into_iter()
+        .map(|(player, (estimate, games))| Rating {
+            rank: 0,
+            player,
+            rating: initial_rating + SCALE * estimate.mu,
+            deviation: Some(SCALE * estimate.phi),
+            games,
+        })
+        .collect()
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+/// The estimate after one rating period with `results`.
+fn update(estimate: Estimate, results: &[(Estimate, f64)], tau: f64) -> Estimate {
+    let Estimate { mu, phi, sigma } = estimate;
+
+    let mut information = 0.0;
+    let mut improvement = 0.0;
+    for (opponent, score) in results {
+        let g = g(opponent.phi);
+        let expected = 1.0 / (1.0 + (-g * (mu - opponent.mu)).exp());
+        information += g * g * expected * (1.0 - expected);
+        improvement += g * (score - expected);
+    }
+    let variance = 1.0 / information;
+    let delta = variance * improvement;
+
+    let sigma = volatility(phi, sigma, variance, delta, tau);
+    let phi_star = phi.hypot(sigma);
+    let phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / variance).sqrt();
+    Estimate { mu: mu + phi * phi * improvement, phi, sigma }
+}
+
+/// The new volatility, found with the Illinois algorithm as in step 5 of
+/// Glickman's description of Glicko-2.
+fn volatility(phi: f64, sigma: f64, variance: f64, delta: f64, tau: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denominator = phi * phi + variance + ex;
+        ex * (delta * delta - phi * phi - variance - ex) / (2.0 * denominator * denominator) - (x - a) / (tau * tau)
+    };
+
+    let mut low = a;
+    let mut high = if delta * delta > phi * phi + variance {
+        (delta * delta - phi * phi - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            k += 1.0;
+        }
+        a - k * tau
+    };
+
+    let (mut f_low, mut f_high) = (f(low), f(high));
+    while (high - low).abs() > CONVERGENCE {
+        let middle = low + (low - high) * f_low / (f_high - f_low);
+        let f_middle = f(middle);
+        if f_middle * f_high <= 0.0 {
+            low = high;
+            f_low = f_high;
+        } else {
+            f_low /= 2.0;
+        }
+        high = middle;
+        f_high = f_middle;
+    }
+
+    (low / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_glickmans_worked_example() {
+        // The example from "Example of the Glicko-2 system": a 1500 (RD 200)
+        // player beats a 1400 (RD 30) and loses to a 1550 (RD 100) and a 1700 (RD 300)
+        let player = Estimate { mu: 0.0, phi: 200.0 / SCALE, sigma: 0.06 };
+        let opponent = |rating: f64, deviation: f64| Estimate { mu: (rating - 1500.0) / SCALE, phi: deviation / SCALE, sigma: 0.06 };
+        let results = [(opponent(1400.0, 30.0), 1.0), (opponent(1550.0, 100.0), 0.0), (opponent(1700.0, 300.0), 0.0)];
+
+        let updated = update(player, &results, 0.5);
+        assert!((1500.0 + SCALE * updated.mu - 1464.06).abs() < 0.01, "{:?}", updated);
+        assert!((SCALE * updated.phi - 151.52).abs() < 0.01, "{:?}", updated);
+        assert!((updated.sigma - 0.05999).abs() < 0.00001, "{:?}", updated);
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::checkpoint;
+use crate::rating::{self, RatingConfig, RatingModel};
+use crate::MatchResult;
+
+/// The `[ladder]` section of the tournament configuration.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LadderConfig {
+    /// JSON file holding every tournament played so far.
+    pub path: String,
+    /// Name this tournament is recorded under; "Tournament <seed>" when not set.
+    pub event: Option<String>,
+    /// Seed the first stage from the ladder ratings instead of drawing at random.
+    #[serde(default)]
+    pub seed_groups: bool,
+}
+
+/// Results of every tournament recorded so far, oldest first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ladder {
+    pub version: u32,
+    pub events: Vec<Event>,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Ladder { version: 1, events: Vec::new() }
+    }
+}
+
+/// One tournament on the ladder.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub name: String,
+    pub seed: u64,
+    pub agents: Vec<String>,
+    pub results: Vec<MatchResult>,
+}
+
+/// One line of the leaderboard.
+#[derive(Debug)]
+pub struct Entry {
+    pub rating: rating::Rating,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Number of tournaments the agent took part in.
+    pub events: usize,
+}
+
+impl Ladder {
+    /// The ladder at `path`, or an empty one if the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Ladder::default());
+        }
+        let contents = fs::read_to_string(path).map_err(|e| format!("Could not read the ladder {}: {}", path, e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("{} is not a ladder file: {}", path, e).into())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        checkpoint::write_atomically(path, self)
+    }
+
+    /// Add a tournament, replacing an earlier one of the same name (a rerun).
+    /// Returns whether an earlier one was replaced.
+    pub fn record(&mut self, event: Event) -> bool {
+        match self.events.iter_mut().find(|earlier| earlier.name == event.name) {
+            Some(earlier) => {
+                *earlier = event;
+                true
+            }
+            None => {
+                self.events.push(event);
+                false
+            }
+        }
+    }
+
+    /// Ratings over every game on the ladder, best first.
+    pub fn ratings(&self, config: &RatingConfig) -> Vec<rating::Rating> {
+        let agents: Vec<String> = self.events.iter().flat_map(|event| event.agents.iter().cloned()).collect();
+        // Rating periods follow round names, so tell apart rounds of the same
+        // name in different events
+        let games: Vec<MatchResult> = self.events.iter()
+            .flat_map(|event| event.results.iter().map(|game| MatchResult {
+                round: format!("{}: {}", event.name, game.round),
+                ..game.clone()
+            }))
+            .collect();
+        rating::rate(&agents, &games, config)
+    }
+
+    /// The leaderboard: ratings with each agent's record, best first.
+    pub fn leaderboard(&self, config: &RatingConfig) -> Vec<Entry> {
+        let mut records: BTreeMap<&str, [u32; 3]> = BTreeMap::new();
+        for game in self.events.iter().flat_map(|event| &event.results).filter(|game| !game.is_no_contest()) {
+            for player in [&game.player1, &game.player2] {
+                let record = records.entry(player).or_default();
+                let index = if game.is_draw {
+                    1
+                } else if game.winner.as_ref() == Some(player) {
+                    0
+                } else {
+                    2
+                };
+                record[index] += 1;
+            }
+        }
+
+        self.ratings(config).into_iter()
+            .map(|rating| {
+                let [wins, draws, losses] = records.get(rating.player.as_str()).copied().unwrap_or_default();
+                let events = self.events.iter().filter(|event| event.agents.contains(&rating.player)).count();
+                Entry { rating, wins, draws, losses, events }
+            })
+            .collect()
+    }
+}
+
+/// Print the leaderboard of `ladder`.
+pub fn print(ladder: &Ladder, config: &RatingConfig) {
+    let tournaments = match ladder.events.len() {
+        1 => "1 tournament".to_string(),
+        count => format!("{} tournaments", count),
+    };
+    println!("\n=== LADDER ({}, {}) ===\n", config.describe(), tournaments);
+    println!("{:<5} {:<20} {:<8} {:<17} {:<5} {:<5} {:<5} {:<5} {:<11}",
+             "Rank", "Player", "Rating", "95% interval", "Games", "W", "D", "L", "Tournaments");
+    println!("{}", "-".repeat(86));
+    for entry in ladder.leaderboard(config) {
+        let rating = &entry.rating;
+        let interval = match rating.interval() {
+            Some((low, high)) if config.model != RatingModel::Elo => format!("{:.1} - {:.1}", low, high),
+            _ => "-".to_string(),
+        };
+        println!("{:<5} {:<20} {:<8.1} {:<17} {:<5} {:<5} {:<5} {:<5} {}",
+                 rating.rank, rating.player, rating.rating, interval, rating.games, entry.wins, entry.draws, entry.losses, entry.events);
+    }
+}
+
+/// `players` ordered for seeding: rated agents by ladder rating, best first,
+/// then everyone without a ladder game in their original order.
+pub fn seed_order(players: &[String], ratings: &[rating::Rating]) -> Vec<String> {
+    let mut ordered: Vec<String> = ratings.iter()
+        .filter(|rating| rating.games > 0 && players.contains(&rating.player))
+        .map(|rating| rating.player.clone())
+        .collect();
+    let unrated: Vec<String> = players.iter().filter(|player| !ordered.contains(player)).cloned().collect();
+    ordered.extend(unrated);
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, results: Vec<MatchResult>) -> Event {
+        let agents = ["a", "b", "c"].iter().map(|agent| agent.to_string()).collect();
+        Event { name: name.to_string(), seed: 1, agents, results }
+    }
+
+    #[test]
+    fn reruns_replace_their_event() {
+        let mut ladder = Ladder::default();
+        assert!(!ladder.record(event("Week 1", vec![MatchResult::test("a", "b", Some("a"))])));
+        assert!(!ladder.record(event("Week 2", vec![MatchResult::test("b", "c", Some("b"))])));
+        assert!(ladder.record(event("Week 1", vec![MatchResult::test("a", "b", Some("b"))])));
+
+        let board = ladder.leaderboard(&RatingConfig::default());
+        let rows: Vec<(&str, u32, u32, usize)> = board.iter()
+            .map(|entry| (entry.rating.player.as_str(), entry.wins, entry.losses, entry.events))
+            .collect();
+        assert_eq!(rows, [("b", 2, 0, 2), ("c", 0, 1, 2), ("a", 0, 1, 2)]);
+    }
+
+    #[test]
+    fn each_event_rates_its_own_rounds() {
+        let week1 = vec![MatchResult::test("a", "b", Some("a"))];
+        let week2 = vec![MatchResult::test("b", "c", Some("b"))];
+        let mut ladder = Ladder::default();
+        ladder.record(event("Week 1", week1.clone()));
+        ladder.record(event("Week 2", week2.clone()));
+
+        // Both events only have a "Round 1", which must still be two rating periods
+        let config = RatingConfig { model: RatingModel::Glicko2, ..RatingConfig::default() };
+        let agents = &ladder.events[0].agents;
+        let periods: Vec<MatchResult> = week1.into_iter()
+            .chain(week2.into_iter().map(|game| MatchResult { round: "Round 2".to_string(), ..game }))
+            .collect();
+        let deviations = |ratings: Vec<rating::Rating>| ratings.into_iter()
+            .map(|rating| (rating.player, rating.deviation.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(deviations(ladder.ratings(&config)), deviations(rating::rate(agents, &periods, &config)));
+        let merged: Vec<MatchResult> = ladder.events.iter().flat_map(|event| event.results.iter().cloned()).collect();
+        assert_ne!(deviations(ladder.ratings(&config)), deviations(rating::rate(agents, &merged, &config)));
+    }
+
+    #[test]
+    fn seeds_rated_agents_first() {
+        let mut ladder = Ladder::default();
+        ladder.record(event("Week 1", vec![MatchResult::test("a", "b", Some("b"))]));
+        let players: Vec<String> = ["a", "c", "new", "b"].iter().map(|player| player.to_string()).collect();
+        assert_eq!(seed_order(&players, &ladder.ratings(&RatingConfig::default())), ["b", "a", "c", "new"]);
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::game::{Game, GameState, IllegalMove, Outcome};
+
+/// Stones each player starts with in hand.
+const HAND_SIZE: u32 = 10;
+
+/// Moves in a row without a capture before the game is drawn. Placements
+/// count too, as in the Python referee.
+const NO_CAPTURE_LIMIT: u32 = 20;
+
+/// Extra time allowed per move, as in the Python referee.
+const TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+
+/// The 24 points of the board.
+const POINTS: [&str; 24] = [
+    "a1", "a4", "a7", "b2", "b4", "b6", "c3", "c4", "c5", "d1", "d2", "d3",
+    "d5", "d6", "d7", "e3", "e4", "e5", "f2", "f4", "f6", "g1", "g4", "g7",
+];
+
+/// Neighbors of each point, in the order of `POINTS`.
+const NEIGHBORS: [&[&str]; 24] = [
+    &["a4", "d1"], &["a1", "a7", "b4"], &["a4", "d7"],
+    &["b4", "d2"], &["b2", "b6", "a4", "c4"], &["b4", "d6"],
+    &["c4", "d3"], &["c3", "c5", "b4"], &["c4", "d5"],
+    &["a1", "d2", "g1"], &["b2", "d1", "d3", "f2"], &["c3", "d2", "e3"],
+    &["c5", "d6", "e5"], &["b6", "d5", "d7", "f6"], &["a7", "d6", "g7"],
+    &["d3", "e4"], &["e3", "e5", "f4"], &["d5", "e4"],
+    &["d2", "f4"], &["e4", "f2", "f6", "g4"], &["d6", "f4"],
+    &["d1", "g4"], &["f4", "g1", "g7"], &["d7", "g4"],
+];
+
+const MILLS: [[&str; 3]; 16] = [
+    ["a1", "a4", "a7"], ["b2", "b4", "b6"], ["c3", "c4", "c5"], ["d1", "d2", "d3"],
+    ["d5", "d6", "d7"], ["e3", "e4", "e5"], ["f2", "f4", "f6"], ["g1", "g4", "g7"],
+    ["a1", "d1", "g1"], ["b2", "d2", "f2"], ["c3", "d3", "e3"], ["a4", "b4", "c4"],
+    ["e4", "f4", "g4"], ["c5", "d5", "e5"], ["b6", "d6", "f6"], ["a7", "d7", "g7"],
+];
+
+fn point(name: &str) -> Option<usize> {
+    POINTS.iter().position(|&p| p == name)
+}
+
+fn adjacent(from: usize, to: usize) -> bool {
+    NEIGHBORS[from].contains(&POINTS[to])
+}
+
+/// Where a stone comes from: the player's hand or a point on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Hand,
+    Point(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    source: Source,
+    target: usize,
+    remove: Option<usize>,
+}
+
+/// Stones on the board and in hand, and whose turn it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Position {
+    board: [Option<usize>; 24],
+    hands: [u32; 2],
+    turn: usize,
+}
+
+impl Position {
+    fn new() -> Self {
+        Position { board: [None; 24], hands: [HAND_SIZE; 2], turn: 0 }
+    }
+
+    fn on_board(&self, slot: usize) -> usize {
+        self.board.iter().filter(|&&stone| stone == Some(slot)).count()
+    }
+
+    /// Stones left to the player, in hand and on the board.
+    fn stones(&self, slot: usize) -> u32 {
+        self.on_board(slot) as u32 + self.hands[slot]
+    }
+
+    fn in_mill(&self, at: usize, slot: usize) -> bool {
+        MILLS.iter().any(|mill| {
+            mill.contains(&POINTS[at]) && mill.iter().all(|&p| self.board[point(p).unwrap()] == Some(slot))
+        })
+    }
+
+    /// Whether moving a stone of the player to move from `source` to
+    /// `target` closes a mill.
+    fn closes_mill(&self, source: Source, target: usize) -> bool {
+        let slot = self.turn;
+        MILLS.iter().filter(|mill| mill.contains(&POINTS[target])).any(|mill| {
+            mill.iter().all(|&p| {
+                let at = point(p).unwrap();
+                at == target || (source != Source::Point(at) && self.board[at] == Some(slot))
+            })
+        })
+    }
+
+    /// Read `text` ("h1 d1 r0") as a move of the player to move and check it
+    /// against the rules.
+    fn parse_move(&self, text: &str) -> Result<Move, String> {
+        let slot = self.turn;
+        let parts: Vec<&str> = text.split_whitespace().collect();
+        let [source, target, remove] = parts[..] else {
+            return Err(format!("'{}' is not of the form 'source target remove'", text));
+        };
+
+        let target = point(target).ok_or_else(|| format!("Invalid target position: {}", target))?;
+        if self.board[target].is_some() {
+            return Err(format!("Target position {} is occupied", POINTS[target]));
+        }
+
+        let source = if source == "h1" || source == "h2" {
+            if source != ["h1", "h2"][slot] {
+                return Err(format!("Invalid hand: {}", source));
+            }
+            if self.hands[slot] == 0 {
+                return Err("No stones left in hand".to_string());
+            }
+            Source::Hand
+        } else {
+            let from = point(source).ok_or_else(|| format!("Invalid source position: {}", source))?;
+            if self.board[from] != Some(slot) {
+                return Err(format!("Not your stone at {}", source));
+            }
+            // Stones only fly once the player is down to three
+            if self.stones(slot) > 3 && !adjacent(from, target) {
+                return Err("Must move to adjacent position".to_string());
+            }
+            Source::Point(from)
+        };
+
+        let mill = self.closes_mill(source, target);
+        let remove = if remove == "r0" {
+            if mill {
+                return Err("Must remove stone after forming mill".to_string());
+            }
+            None
+        } else {
+            let at = point(remove).ok_or_else(|| format!("Invalid remove position: {}", remove))?;
+            let opponent = 1 - slot;
+            match self.board[at] {
+                None => return Err(format!("No stone at position {}", remove)),
+                Some(owner) if owner == slot => return Err("Cannot remove your own stone".to_string()),
+                Some(_) => {}
+            }
+            // Stones in a mill are safe unless every stone is
+            let all_in_mills = (0..POINTS.len())
+                .filter(|&p| self.board[p] == Some(opponent))
+                .all(|p| self.in_mill(p, opponent));
+            if self.in_mill(at, opponent) && !all_in_mills {
+                return Err("Cannot remove stone in mill when stones outside mills exist".to_string());
+            }
+            if !mill {
+                return Err("Can only remove when forming a mill".to_string());
+            }
+            Some(at)
+        };
+
+        Ok(Move { source, target, remove })
+    }
+
+    fn apply(&mut self, mv: Move) {
+        let slot = self.turn;
+        match mv.source {
+            Source::Hand => self.hands[slot] -= 1,
+            Source::Point(from) => self.board[from] = None,
+        }
+        self.board[mv.target] = Some(slot);
+        if let Some(at) = mv.remove {
+            self.board[at] = None;
+        }
+        self.turn = 1 - slot;
+    }
+
+    /// Whether the player to move has any legal move at all.
+    fn can_move(&self) -> bool {
+        let slot = self.turn;
+        let empty = || self.board.iter().any(Option::is_none);
+        if self.hands[slot] > 0 || self.on_board(slot) == 3 {
+            return empty();
+        }
+        (0..POINTS.len())
+            .filter(|&from| self.board[from] == Some(slot))
+            .any(|from| (0..POINTS.len()).any(|to| self.board[to].is_none() && adjacent(from, to)))
+    }
+}
+
+/// The Python referee's `laskermorris` game: colors first, then one
+/// "source target remove" move per line, and "END" to both players at the end.
+#[derive(Debug)]
+pub struct LaskerMorris;
+
+impl Game for LaskerMorris {
+    fn name(&self) -> &'static str {
+        "laskermorris"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["lasker_morris", "lasker-morris"]
+    }
+
+    fn end_message(&self) -> Option<&'static str> {
+        Some("END")
+    }
+
+    fn timeout_grace(&self) -> Duration {
+        TIMEOUT_GRACE
+    }
+
+    fn new_state(&self) -> Box<dyn GameState> {
+        Box::new(State::new(Position::new()))
+    }
+}
+
+/// A game in progress, with the counters behind the draw rules.
+struct State {
+    position: Position,
+    without_capture: u32,
+    seen: HashMap<Position, u32>,
+}
+
+impl State {
+    fn new(position: Position) -> Self {
+        let seen = HashMap::from([(position.clone(), 1)]);
+        State { position, without_capture: 0, seen }
+    }
+}
+
+impl GameState for State {
+    fn turn(&self) -> usize {
+        self.position.turn
+    }
+
+    fn before_move(&self) -> Option<Outcome> {
+        (!self.position.can_move()).then_some((Some(1 - self.position.turn), "no_valid_moves"))
+    }
+
+    fn play(&mut self, text: &str) -> Result<Option<Outcome>, IllegalMove> {
+        let mv = self.position.parse_move(text).map_err(|reason| IllegalMove {
+            well_formed: text.split_whitespace().count() == 3,
+            reason,
+        })?;
+        let slot = self.position.turn;
+        self.position.apply(mv);
+
+        self.without_capture = if mv.remove.is_some() { 0 } else { self.without_capture + 1 };
+        if self.without_capture >= NO_CAPTURE_LIMIT {
+            return Ok(Some((None, "no_capture_limit")));
+        }
+        if self.position.stones(1 - slot) < 3 {
+            return Ok(Some((Some(slot), "fewer_than_three_pieces")));
+        }
+        let seen = self.seen.entry(self.position.clone()).or_insert(0);
+        *seen += 1;
+        if *seen >= 3 {
+            return Ok(Some((None, "threefold_repetition")));
+        }
+        Ok(None)
+    }
+
+    /// Laid out like the Python referee's debug output (row 1 at the top),
+    /// with blue stones as "B", orange as "O" and the stones left in hand below.
+    fn board(&self) -> String {
+        let mut lines = Vec::new();
+        for row in '1'..='7' {
+            let line: String = ('a'..='g').map(|column| {
+                match point(&format!("{}{}", column, row)) {
+                    Some(at) => match self.position.board[at] {
+                        Some(0) => " B",
+                        Some(_) => " O",
+                        None => " .",
+                    },
+                    None => "  ",
+                }
+            }).collect();
+            lines.push(format!("{}{}", row, line.trim_end()));
+        }
+        lines.push("  a b c d e f g".to_string());
+        lines.push(String::new());
+        lines.push("Stones in hand:".to_string());
+        lines.push(format!("Blue: {}", self.position.hands[0]));
+        lines.push(format!("Orange: {}", self.position.hands[1]));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A position after placement with the given stones for blue and orange.
+    fn position(blue: &[&str], orange: &[&str], turn: usize) -> Position {
+        let mut position = Position { board: [None; 24], hands: [0; 2], turn };
+        for (slot, stones) in [blue, orange].iter().enumerate() {
+            for stone in stones.iter() {
+                position.board[point(stone).unwrap()] = Some(slot);
+            }
+        }
+        position
+    }
+
+    #[test]
+    fn board_tables_are_consistent() {
+        for from in 0..POINTS.len() {
+            for to in NEIGHBORS[from].iter().map(|name| point(name).unwrap()) {
+                assert!(adjacent(to, from), "{} -> {}", POINTS[from], POINTS[to]);
+            }
+        }
+        assert!(MILLS.iter().flatten().all(|name| point(name).is_some()));
+    }
+
+    #[test]
+    fn placement_and_mills() {
+        let mut game = State::new(Position::new());
+        assert!(game.play("h2 d1 r0").unwrap_err().reason.contains("Invalid hand"));
+        for text in ["h1 a1 r0", "h2 g1 r0", "h1 a4 r0", "h2 g4 r0"] {
+            assert_eq!(game.play(text), Ok(None));
+        }
+        assert!(game.play("h1 a7 r0").unwrap_err().reason.contains("Must remove"));
+        assert!(game.play("h1 d7 g1").unwrap_err().reason.contains("Can only remove"));
+        assert!(game.play("h1 a7 d4").unwrap_err().reason.contains("Invalid remove"));
+        assert_eq!(game.play("h1 a7 g1"), Ok(None));
+        assert_eq!(game.position.stones(1), 9);
+    }
+
+    #[test]
+    fn stones_in_a_mill_are_safe_unless_all_are() {
+        let blue = ["a1", "a4", "b2", "d7"];
+        let position = position(&blue, &["g1", "g4", "g7", "e3"], 0);
+        assert!(position.parse_move("d7 a7 g4").unwrap_err().contains("Cannot remove stone in mill"));
+        assert!(position.parse_move("d7 a7 e3").is_ok());
+
+        let position = self::position(&blue, &["g1", "g4", "g7"], 0);
+        assert!(position.parse_move("d7 a7 g4").is_ok());
+    }
+
+    #[test]
+    fn board_shows_stones_and_hands() {
+        let mut game = State::new(Position::new());
+        game.play("h1 a1 r0").unwrap();
+        game.play("h2 g7 r0").unwrap();
+        let board = game.board();
+        assert!(board.starts_with("1 B     .     .\n"), "{}", board);
+        assert!(board.contains("\n7 .     .     O\n  a b c d e f g\n\nStones in hand:\nBlue: 9\nOrange: 9"), "{}", board);
+    }
+
+    #[test]
+    fn stones_fly_at_three() {
+        let position = position(&["a1", "b2", "c3", "d5"], &["g1", "g4", "g7", "e3"], 0);
+        assert!(position.parse_move("a1 f6 r0").unwrap_err().contains("adjacent"));
+        assert!(position.parse_move("a1 a4 r0").is_ok());
+
+        let position = self::position(&["a1", "b2", "c3"], &["g1", "g4", "g7", "e3"], 0);
+        assert!(position.parse_move("a1 f6 r0").is_ok());
+    }
+
+    #[test]
+    fn capture_to_two_stones_wins() {
+        let mut game = State::new(position(&["a1", "a4", "b6"], &["g1", "g4", "e3"], 0));
+        assert_eq!(game.play("b6 a7 e3"), Ok(Some((Some(0), "fewer_than_three_pieces"))));
+    }
+
+    #[test]
+    fn blocked_player_cannot_move() {
+        let position = position(&["a1", "a4", "d2", "b2"], &["d1", "a7", "b4", "d3", "c3", "f2"], 0);
+        assert!(!position.can_move());
+        assert_eq!(State::new(position).before_move(), Some((Some(1), "no_valid_moves")));
+    }
+
+    #[test]
+    fn threefold_repetition_is_a_draw() {
+        let mut game = State::new(position(&["a1", "b2", "c3", "e5"], &["g1", "g4", "f6", "e3"], 0));
+        let shuffle = ["a1 a4 r0", "g1 d1 r0", "a4 a1 r0", "d1 g1 r0"];
+        for text in shuffle.iter().chain(&shuffle[..3]) {
+            assert_eq!(game.play(text), Ok(None));
+        }
+        assert_eq!(game.play("d1 g1 r0"), Ok(Some((None, "threefold_repetition"))));
+    }
+}
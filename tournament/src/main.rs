@@ -1,21 +1,60 @@
 use csv::Writer;
-use log::{debug, info, warn, LevelFilter};
+use log::{info, warn};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::process::{Command, Stdio};
 use std::fs;
-use toml;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use simplelog::{WriteLogger, Config};
 
+mod agent;
+mod bracket;
+mod checkpoint;
+mod cli;
+mod game;
+mod glicko2;
+mod ladder;
+mod laskermorris;
+mod native;
+mod rating;
+mod replay;
+mod runner;
+mod stage;
+mod swiss;
+mod tictactoe;
+mod tiebreak;
+mod transcript;
+mod trueskill;
+mod validate;
+mod vars;
+
+use checkpoint::Checkpoint;
+use ladder::{Ladder, LadderConfig};
+use rating::{RatingConfig, RatingModel};
+use runner::{MatchRunner, PlayGame};
+
+use stage::{StageConfig, StageFormat};
+use tiebreak::{Standing, Tiebreak, Tiebreaker};
+
 #[derive(Debug, Deserialize)]
 struct TournamentConfig {
     game: String,
     settings: Option<GameSettings>,
-    groups: Option<HashMap<String, Vec<String>>>,
-    agents: HashMap<String, String>,
+    groups: Option<BTreeMap<String, Vec<String>>>,
+    agents: BTreeMap<String, String>,
+    stages: Option<Vec<StageConfig>>,
+    output: Option<OutputConfig>,
+    scoring: Option<Scoring>,
+    ratings: Option<RatingConfig>,
+    ladder: Option<LadderConfig>,
+    /// Seed of each agent for the first stage, 1 being the strongest.
+    seeds: Option<BTreeMap<String, u32>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -24,19 +63,246 @@ struct GameSettings {
     visual: Option<bool>,
     random_assignment: Option<bool>,
     debug: Option<bool>,
-    port: Option<i32>,
+    port: Option<u16>,
+    /// First and last port (inclusive) handed out to visual games.
+    port_range: Option<(u16, u16)>,
+    seed: Option<u64>,
+    parallelism: Option<usize>,
+    engine: Option<Engine>,
+    /// How players level on points are ordered, first tiebreaker first.
+    tiebreaks: Option<Vec<Tiebreak>>,
+    /// Most playoff games between two players tied on the cut line.
+    playoff_games: Option<usize>,
+}
+
+/// Who referees the games: the Python referee or the built-in Rust engine.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+enum Engine {
+    #[default]
+    Referee,
+    Native,
+}
+
+/// Playoff rounds played at most when `playoff_games` is not set.
+const DEFAULT_PLAYOFF_GAMES: usize = 3;
+
+/// Ports tried when `port_range` is not set, counting up from `port`.
+const DEFAULT_PORT_COUNT: u16 = 100;
+
+impl GameSettings {
+    fn port_range(&self) -> RangeInclusive<u16> {
+        match self.port_range {
+            Some((first, last)) => first..=last,
+            None => {
+                let first = self.port.unwrap_or(8000);
+                first..=first.saturating_add(DEFAULT_PORT_COUNT - 1)
+            }
+        }
+    }
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            timeout: Some(5),
+            visual: Some(false),
+            random_assignment: Some(false),
+            debug: Some(false),
+            port: Some(8000),
+            port_range: None,
+            seed: None,
+            parallelism: None,
+            engine: None,
+            tiebreaks: None,
+            playoff_games: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+struct OutputConfig {
+    results: Option<String>,
+    checkpoint: Option<String>,
+    /// Directory for game transcripts; an empty string turns them off.
+    transcripts: Option<String>,
+    /// CSV file for the final ratings; an empty string turns it off.
+    ratings: Option<String>,
+}
+
+impl OutputConfig {
+    fn results_path(&self) -> String {
+        self.results.clone().unwrap_or_else(|| "tournament_results.csv".to_string())
+    }
+
+    fn checkpoint_path(&self) -> String {
+        self.checkpoint.clone().unwrap_or_else(|| "tournament_state.json".to_string())
+    }
+
+    fn transcripts_dir(&self) -> Option<String> {
+        match self.transcripts.as_deref() {
+            Some("") => None,
+            Some(dir) => Some(dir.to_string()),
+            None => Some("transcripts".to_string()),
+        }
+    }
+
+    fn ratings_path(&self) -> Option<String> {
+        match self.ratings.as_deref() {
+            Some("") => None,
+            Some(path) => Some(path.to_string()),
+            None => Some("ratings.csv".to_string()),
+        }
+    }
+}
+
+/// Points for each kind of result.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+struct Scoring {
+    win: f32,
+    draw: f32,
+    loss: f32,
+    /// Points for losing by forfeit; a plain loss when not set.
+    forfeit: Option<f32>,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Scoring { win: 2.0, draw: 1.0, loss: 0.0, forfeit: None }
+    }
+}
+
+impl Scoring {
+    /// Points `player` earns from `result`, or `None` if they did not play
+    /// in it or the game does not count.
+    fn points(&self, result: &MatchResult, player: &str) -> Option<f32> {
+        if (result.player1 != player && result.player2 != player) || result.is_no_contest() {
+            return None;
+        }
+        Some(if result.is_draw {
+            self.draw
+        } else if result.winner.as_deref() == Some(player) {
+            self.win
+        } else if result.forfeit.is_some() {
+            self.forfeit.unwrap_or(self.loss)
+        } else {
+            self.loss
+        })
+    }
+}
+
+/// A game waiting to be played. `player1` moves first.
+#[derive(Debug, Clone)]
+struct ScheduledMatch {
+    group: String,
+    game_number: i32,
+    player1: String,
+    player2: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl ScheduledMatch {
+    fn new(group: &str, game_number: i32, player1: &str, player2: &str) -> Self {
+        ScheduledMatch {
+            group: group.to_string(),
+            game_number,
+            player1: player1.to_string(),
+            player2: player2.to_string(),
+        }
+    }
+}
+
+/// Why a game was lost without being played out. The game goes to the
+/// opponent of whoever caused it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Forfeit {
+    /// The agent process exited or could not be started.
+    Crash,
+    /// A well-formed move that breaks the rules.
+    InvalidMove,
+    /// No move within the time limit.
+    Timeout,
+    /// Output that is not a move at all.
+    ProtocolViolation,
+}
+
+impl Forfeit {
+    fn parse(reason: &str) -> Option<Self> {
+        match reason {
+            "crash" => Some(Forfeit::Crash),
+            "invalid_move" => Some(Forfeit::InvalidMove),
+            "timeout" => Some(Forfeit::Timeout),
+            "protocol_violation" => Some(Forfeit::ProtocolViolation),
+            _ => None,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Forfeit::Crash => "crash",
+            Forfeit::InvalidMove => "invalid move",
+            Forfeit::Timeout => "timeout",
+            Forfeit::ProtocolViolation => "protocol violation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct MatchResult {
     round: String,
     group: String,
     game_number: i32,
     player1: String,
     player2: String,
+    /// Side each player had ("blue" / "orange"); player 1 moves first.
+    player1_color: Option<String>,
+    player2_color: Option<String>,
     winner: Option<String>,
     is_draw: bool,
+    /// Set when the loser forfeited instead of losing over the board.
+    forfeit: Option<Forfeit>,
+    /// How the game ended, as reported by the referee (e.g. "three_in_a_row").
+    termination: Option<String>,
+    moves: Option<u32>,
+    /// Seconds each player spent thinking over the whole game.
+    player1_time: Option<f64>,
+    player2_time: Option<f64>,
     error: Option<String>,
+    /// Path of the game's transcript file.
+    #[serde(default)]
+    transcript: Option<String>,
+}
+
+impl MatchResult {
+    /// A game with neither a winner nor a draw could not be attributed to
+    /// either player and does not count for anyone.
+    fn is_no_contest(&self) -> bool {
+        self.winner.is_none() && !self.is_draw
+    }
+
+    /// A finished game for tests: `winner` wins, or it is a draw when `None`.
+    #[cfg(test)]
+    fn test(player1: &str, player2: &str, winner: Option<&str>) -> Self {
+        MatchResult {
+            round: "Round 1".to_string(),
+            group: "Group A".to_string(),
+            game_number: 1,
+            player1: player1.to_string(),
+            player2: player2.to_string(),
+            player1_color: None,
+            player2_color: None,
+            winner: winner.map(str::to_string),
+            is_draw: winner.is_none(),
+            forfeit: None,
+            termination: None,
+            moves: None,
+            player1_time: None,
+            player2_time: None,
+            error: None,
+            transcript: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +311,8 @@ struct PlayerStats {
     wins: i32,
     losses: i32,
     draws: i32,
+    /// Losses by forfeit (also counted in `losses`).
+    forfeits: i32,
     points: f32,
 }
 
@@ -55,19 +323,30 @@ impl PlayerStats {
             wins: 0,
             losses: 0,
             draws: 0,
+            forfeits: 0,
             points: 0.0,
         }
     }
 
-    fn add_result(&mut self, result: &MatchResult) {
+    fn add_bye(&mut self, scoring: &Scoring) {
+        self.wins += 1;
+        self.points += scoring.win;
+    }
+
+    fn add_result(&mut self, result: &MatchResult, scoring: &Scoring) {
+        let Some(points) = scoring.points(result, &self.name) else {
+            return;
+        };
+        self.points += points;
         if result.is_draw {
             self.draws += 1;
-            self.points += 1.0;
         } else if Some(self.name.clone()) == result.winner {
             self.wins += 1;
-            self.points += 2.0;
         } else {
             self.losses += 1;
+            if result.forfeit.is_some() {
+                self.forfeits += 1;
+            }
         }
     }
 }
@@ -75,351 +354,634 @@ impl PlayerStats {
 #[derive(Debug)]
 struct TournamentManager {
     config: TournamentConfig,
+    stages: Vec<StageConfig>,
+    stage_index: usize,
     round_results: HashMap<String, Vec<MatchResult>>,
+    /// Playoff games of each round, kept apart so they only count as a tiebreaker.
+    playoff_results: HashMap<String, Vec<MatchResult>>,
+    /// Groups whose games are playoffs.
+    playoff_groups: HashSet<String>,
     current_round: String,
     csv_writer: Writer<File>,
-    groups: HashMap<String, Vec<String>>,
-    player_stats: HashMap<String, HashMap<String, HashMap<String, PlayerStats>>>,
+    groups: BTreeMap<String, Vec<String>>,
+    player_stats: HashMap<String, BTreeMap<String, BTreeMap<String, PlayerStats>>>,
+    /// Swiss byes handed out so far, as (round, group, player).
+    byes: Vec<(String, String, String)>,
+    /// Entrants of the current elimination stage, best seed first.
+    bracket_seeds: Vec<String>,
+    /// Final order of every finished elimination stage, keyed by round.
+    bracket_rankings: HashMap<String, Vec<String>>,
+    /// Every game of the tournament so far, in the order it was played.
+    played: Vec<MatchResult>,
+    /// Drives every random decision so a resumed run draws the same groups.
+    seed: u64,
+    rng: StdRng,
+    /// Checkpointed results not yet replayed, keyed by (round, group, game number).
+    completed: HashMap<(String, String, i32), MatchResult>,
+    /// Where the checkpoint left off, checked once its games are replayed.
+    resume_point: Option<checkpoint::Position>,
+    /// How many games may run at the same time.
+    jobs: usize,
+    runner: Arc<dyn PlayGame>,
 }
 
 impl TournamentManager {
-    fn new(config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // Read and parse the tournament configuration
-        let config_str = fs::read_to_string(config_path)?;
-        let config: TournamentConfig = toml::from_str(&config_str)?;
+    fn new(config: TournamentConfig, options: &cli::Options) -> Result<Self, Box<dyn std::error::Error>> {
+        let output = config.output.clone().unwrap_or_default();
+
+        // Pick up the seed and finished games of an interrupted run
+        let checkpoint = if options.resume {
+            let checkpoint = Checkpoint::load(&output.checkpoint_path())?;
+            info!("Resuming from {} with {} completed games (stopped in {})",
+                  output.checkpoint_path(), checkpoint.results.len(), checkpoint.round);
+            Some(checkpoint)
+        } else {
+            None
+        };
+
+        // --random-seed wins over the config; a resumed run must keep its original seed
+        let requested_seed = options.random_seed.or(config.settings.as_ref().and_then(|settings| settings.seed));
+        let seed = match (&checkpoint, requested_seed) {
+            (Some(checkpoint), Some(seed)) if seed != checkpoint.seed => {
+                return Err(format!("The checkpoint was played with seed {}, not {}", checkpoint.seed, seed).into());
+            }
+            (Some(checkpoint), _) => checkpoint.seed,
+            (None, Some(seed)) => seed,
+            (None, None) => thread_rng().gen(),
+        };
+        info!("Random seed: {}", seed);
+
+        // --jobs wins over settings.parallelism; games run one at a time by default
+        let jobs = options.jobs
+            .or(config.settings.as_ref().and_then(|settings| settings.parallelism))
+            .unwrap_or(1);
+        info!("Running up to {} games at a time", jobs);
+
+        // Fall back to the classic five round layout when no pipeline is configured
+        let stages = config.stages.clone().unwrap_or_else(stage::default_stages);
+        if stages.is_empty() {
+            return Err("The [[stages]] list must contain at least one stage".into());
+        }
+        let current_round = stages[0].name.clone();
+        if let Some(checkpoint) = &checkpoint {
+            checkpoint.check_stages(&stages)?;
+        }
+
+        // Create the CSV writer for results; the header is written by hand
+        let file = File::create(output.results_path())?;
+        let csv_writer = csv::WriterBuilder::new().has_headers(false).from_writer(file);
 
-        // Create the CSV writer for results
-        let file = File::create("tournament_results.csv")?;
-        let csv_writer = csv::Writer::from_writer(file);
+        let resume_point = checkpoint.as_ref().map(Checkpoint::position).filter(|position| position.games > 0);
+        let completed = checkpoint.map(|checkpoint| checkpoint.results).unwrap_or_default()
+            .into_iter()
+            .map(|result| ((result.round.clone(), result.group.clone(), result.game_number), result))
+            .collect();
 
         // Initialize round results
         let round_results = HashMap::new();
 
         let player_stats = HashMap::new();
 
+        let game = game::find(&config.game).ok_or_else(|| format!("Unsupported game type: {}", config.game))?;
+        let mut runner = MatchRunner::new(game, &config.agents, config.settings.clone().unwrap_or_default());
+        runner.transcripts = output.transcripts_dir().map(PathBuf::from);
+        let runner = Arc::new(runner);
+
         Ok(TournamentManager {
             config,
+            stages,
+            stage_index: 0,
             round_results,
-            current_round: "First Round".to_string(),
+            playoff_results: HashMap::new(),
+            playoff_groups: HashSet::new(),
+            current_round,
             csv_writer,
-            groups: HashMap::new(),
+            groups: BTreeMap::new(),
             player_stats,
+            byes: Vec::new(),
+            bracket_seeds: Vec::new(),
+            bracket_rankings: HashMap::new(),
+            played: Vec::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            completed,
+            resume_point,
+            jobs,
+            runner,
         })
     }
 
-    fn initialize_groups(&mut self) {
+    fn current_stage(&self) -> &StageConfig {
+        &self.stages[self.stage_index]
+    }
+
+    /// Ranking of all agents for seeding the first stage, from the `[seeds]`
+    /// table or the ladder, or `None` when the first stage is unseeded.
+    /// Agents without a seed or a ladder game come last, in random order.
+    fn first_stage_ranking(&mut self) -> Result<Option<Vec<String>>, Box<dyn std::error::Error>> {
+        let mut players: Vec<String> = self.config.agents.keys().cloned().collect();
+
+        if let Some(seeds) = &self.config.seeds {
+            players.shuffle(&mut self.rng);
+            players.sort_by_key(|player| seeds.get(player).copied().unwrap_or(u32::MAX));
+            info!("Seeding {} from the seeds in the config:", self.current_round);
+            for (i, player) in players.iter().enumerate() {
+                match seeds.get(player) {
+                    Some(seed) => info!("{}. {} (seed {})", i + 1, player, seed),
+                    None => info!("{}. {} (unseeded)", i + 1, player),
+                }
+            }
+            return Ok(Some(players));
+        }
+
+        let Some(ladder) = self.config.ladder.as_ref().filter(|ladder| ladder.seed_groups) else {
+            return Ok(None);
+        };
+        players.shuffle(&mut self.rng);
+        let ratings = Ladder::load(&ladder.path)?.ratings(&self.config.ratings.unwrap_or_default());
+        let seeds = ladder::seed_order(&players, &ratings);
+        info!("Seeding {} from the ladder in {}:", self.current_round, ladder.path);
+        for (i, player) in seeds.iter().enumerate() {
+            match ratings.iter().find(|rating| &rating.player == player && rating.games > 0) {
+                Some(rating) => info!("{}. {} ({:.1})", i + 1, player, rating.rating),
+                None => info!("{}. {} (unrated)", i + 1, player),
+            }
+        }
+        Ok(Some(seeds))
+    }
+
+    fn initialize_groups(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         info!("Initializing tournament groups");
 
+        let ranking = self.first_stage_ranking()?;
+
+        if self.current_stage().format.is_elimination() {
+            if self.config.groups.is_some() {
+                warn!("Predefined groups are ignored when the first stage is a bracket");
+            }
+            let seeds = ranking.unwrap_or_else(|| {
+                let mut players: Vec<String> = self.config.agents.keys().cloned().collect();
+                players.shuffle(&mut self.rng);
+                players
+            });
+            self.setup_bracket(&seeds);
+            return Ok(());
+        }
+
         // If groups are predefined in config, use them
         if let Some(predefined_groups) = &self.config.groups {
             self.groups = predefined_groups.clone();
             info!("Using predefined groups from config");
+        } else if let Some(ranking) = ranking {
+            // A ranked field is dealt in snake order unless the stage asks for pots
+            let stage = self.current_stage().clone();
+            let seeding = match stage.seeding {
+                stage::Seeding::Random => stage::Seeding::Snake,
+                seeding => seeding,
+            };
+            self.assign_groups(&ranking, &stage, seeding);
+            self.print_draw(&ranking, &stage, seeding);
         } else {
-            // Otherwise, create random groups for the first round
-            let mut players: Vec<String> = self.config.agents.keys().cloned().collect();
-            let mut rng = thread_rng();
-            players.shuffle(&mut rng);
-
-            let num_players = players.len();
-            let target_groups = 8; // We want 8 groups for the first round
-
-            // Ensure we don't create more groups than players
-            let num_groups = std::cmp::min(target_groups, num_players);
-
-            self.groups.clear();
-
-            // Calculate base size and remainder
-            let base_size = num_players / num_groups;
-            let remainder = num_players % num_groups;
-
-            let mut start = 0;
-            for i in 0..num_groups {
-                // Groups with index < remainder get one extra player
-                let group_size = if i < remainder { base_size + 1 } else { base_size };
-                let end = start + group_size;
-
-                let group_name = format!("Group {}", (b'A' + i as u8) as char);
-                let group_players = players[start..end].to_vec();
-
-                self.groups.insert(group_name, group_players);
-
-                start = end;
+            // Otherwise, draw the groups for the first stage at random
+            let players: Vec<String> = self.config.agents.keys().cloned().collect();
+            let stage = self.current_stage().clone();
+            if stage.seeding != stage::Seeding::Random {
+                warn!("{} has no prior standings or seeds to seed from; drawing groups at random", stage.name);
             }
+            self.assign_groups(&players, &stage, stage::Seeding::Random);
 
-            info!("Created random groups for First Round");
+            info!("Created random groups for {}", self.current_round);
         }
 
-        // Initialize player stats for this round
-        let mut round_stats = HashMap::new();
-        for (group, players) in &self.groups {
-            let mut group_stats = HashMap::new();
-            for player in players {
-                let player_stats = PlayerStats::new(player);
-                group_stats.insert(player.clone(), player_stats);
-            }
-            round_stats.insert(group.clone(), group_stats);
-        }
-        self.player_stats.insert(self.current_round.clone(), round_stats);
+        self.reset_round_stats();
 
         // Log the groups
         for (group, players) in &self.groups {
             info!("{}: {}", group, players.join(", "));
         }
+
+        Ok(())
     }
 
     fn run_tournament(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Initialize first round groups
-        self.initialize_groups();
         self.write_csv_header()?;
+        println!("Random seed: {}", self.seed);
+
+        // Players entering the next stage, best seed first
+        let mut entrants = Vec::new();
 
-        // Run First Round
-        info!("Starting First Round");
-        self.current_round = "First Round".to_string();
-        self.run_round()?;
-        let first_round_winners = self.determine_winners();
-
-        // Run Second Round
-        info!("Starting Second Round");
-        self.current_round = "Second Round".to_string();
-        self.setup_next_round(&first_round_winners, 4)?; // 4 groups of 4 teams each
-        self.run_round()?;
-        let second_round_winners = self.determine_winners();
-
-        // Run Third Round
-        info!("Starting Third Round");
-        self.current_round = "Third Round".to_string();
-        self.setup_next_round(&second_round_winners, 2)?; // 2 groups of 4 teams each
-        self.run_round()?;
-        let third_round_winners = self.determine_winners();
-
-        // Run Fourth Round
-        info!("Starting Fourth Round");
-        self.current_round = "Fourth Round".to_string();
-        self.setup_next_round(&third_round_winners, 1)?; // 1 group of 4 teams
-        self.run_round()?;
-        let fourth_round_results = self.determine_fourth_round_rankings();
-
-        // Run Final Round
-        info!("Starting Final Round");
-        self.current_round = "Final Round".to_string();
-        self.setup_finals(&fourth_round_results)?;
-        self.run_round()?;
+        for index in 0..self.stages.len() {
+            self.stage_index = index;
+            self.current_round = self.stages[index].name.clone();
+            info!("Starting {}", self.current_round);
+
+            if index == 0 {
+                self.initialize_groups()?;
+            } else {
+                self.setup_next_round(&entrants)?;
+            }
+            self.run_round()?;
+            entrants = self.determine_advancing();
+        }
+
+        // The schedule ended before reaching the end of the checkpoint
+        if let Some(position) = &self.resume_point {
+            position.check(self.stage_index, &self.current_round, &self.groups, self.completed.len())?;
+        }
 
         // Print final results
         self.print_final_results();
         self.print_overall_stats();
+        self.report_ratings()?;
+        self.update_ladder()?;
 
         Ok(())
     }
 
     fn run_round(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut all_results = Vec::new();
+        self.round_results.insert(self.current_round.clone(), Vec::new());
+
+        match self.current_stage().format {
+            StageFormat::Swiss => self.run_swiss_rounds()?,
+            StageFormat::RoundRobin | StageFormat::Placement => self.run_round_robin()?,
+            StageFormat::SingleElimination | StageFormat::DoubleElimination => self.run_bracket()?,
+        }
+
+        self.update_player_stats();
+
+        // Ties on the cut line only matter when someone moves on
+        let grouped = self.current_stage().format.is_grouped();
+        if grouped && self.stage_index + 1 < self.stages.len() {
+            self.play_playoffs()?;
+        }
 
-        for (group_name, players) in &self.groups.clone() {
-            info!("Running matches for {}: {}", group_name, players.join(", "));
+        // Display current standings
+        self.print_standings();
+
+        Ok(())
+    }
+
+    fn run_round_robin(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // Every game of the stage is known up front, so they all go in one batch
+        let mut matches = Vec::new();
+        let random_assignment = self.config.settings.as_ref()
+            .and_then(|settings| settings.random_assignment)
+            .unwrap_or(false);
+
+        for (group_name, players) in &self.groups {
+            info!("Scheduling matches for {}: {}", group_name, players.join(", "));
 
             let mut game_number = 1;
 
             // Each player plays against every other player in their group
             for i in 0..players.len() {
                 for j in (i+1)..players.len() {
-                    // Play two matches with swapped positions
-                    for swap in [false, true] {
+                    // Play two matches with swapped positions. Random assignment
+                    // only picks who moves first in the first of them, so both
+                    // players still move first once.
+                    let first_leg = random_assignment && self.rng.gen_bool(0.5);
+                    for swap in [first_leg, !first_leg] {
                         let (player1, player2) = if swap {
                             (&players[j], &players[i])
                         } else {
                             (&players[i], &players[j])
                         };
 
-                        info!("Match: {} vs {}", player1, player2);
-
-                        // Run the match
-                        let result = self.run_match(
-                            &self.current_round,
-                            group_name,
-                            game_number,
-                            player1,
-                            player2,
-                        )?;
-
-                        // Record the result
-                        self.record_result(&result)?;
-                        all_results.push(result);
-
+                        matches.push(ScheduledMatch::new(group_name, game_number, player1, player2));
                         game_number += 1;
                     }
                 }
             }
         }
 
-        // Save the results for this round
-        self.round_results.insert(self.current_round.clone(), all_results);
-        self.update_player_stats();
-
-        // Display current standings
-        self.print_standings();
+        self.play_matches(matches)?;
 
         Ok(())
     }
 
-    fn run_match(
-        &self,
-        _round: &str,
-        group: &str,
-        game_number: i32,
-        player1: &str,
-        player2: &str,
-    ) -> Result<MatchResult, Box<dyn std::error::Error>> {
-        let p1_cmd = self.config.agents.get(player1).unwrap();
-        let p2_cmd = self.config.agents.get(player2).unwrap();
-
-        // Set up the command arguments based on the game type
-        let settings = self.config.settings.clone().unwrap_or_else(|| {
-            GameSettings {
-                timeout: Some(5),
-                visual: Some(false),
-                random_assignment: Some(false),
-                debug: Some(false),
-                port: Some(8000),
-            }
-        });
+    fn run_swiss_rounds(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let largest_group = self.groups.values().map(Vec::len).max().unwrap_or(0);
+        let rounds = self.current_stage().rounds
+            .unwrap_or_else(|| swiss::default_rounds(largest_group));
 
-        let mut cmd_args = Vec::new();
+        for swiss_round in 1..=rounds {
+            info!("{}: Swiss round {} of {}", self.current_round, swiss_round, rounds);
 
-        // Determine which game to run
-        let game_cmd = match self.config.game.as_str() {
-            "tictactoe" => "tictactoe",
-            "laskermorris" | "lasker_morris" | "lasker-morris" => "laskermorris",
-            _ => {
-                return Err(format!("Unsupported game type: {}", self.config.game).into());
-            }
-        };
+            let mut matches = Vec::new();
+            for (group_name, players) in &self.groups.clone() {
+                let pairing = swiss::pair_round(&self.swiss_entries(group_name, players));
 
-        cmd_args.push(game_cmd.to_string());
-        cmd_args.push("--player1".to_string());
-        cmd_args.push(p1_cmd.clone());
-        cmd_args.push("--player2".to_string());
-        cmd_args.push(p2_cmd.clone());
+                if let Some(player) = pairing.bye {
+                    info!("{} gets a bye in Swiss round {}", player, swiss_round);
+                    self.byes.push((self.current_round.clone(), group_name.clone(), player));
+                }
 
-        // Add optional settings
-        if let Some(timeout) = settings.timeout {
-            cmd_args.push("--timeout".to_string());
-            cmd_args.push(timeout.to_string());
-        }
+                // Game numbers keep counting across Swiss rounds within a group
+                let played = self.round_results[&self.current_round].iter()
+                    .filter(|result| &result.group == group_name)
+                    .count() as i32;
 
-        if let Some(visual) = settings.visual {
-            if visual {
-                cmd_args.push("--visual".to_string());
-            } else {
-                cmd_args.push("--no-visual".to_string());
+                for (offset, (player1, player2)) in pairing.games.iter().enumerate() {
+                    matches.push(ScheduledMatch::new(group_name, played + offset as i32 + 1, player1, player2));
+                }
             }
-        } else {
-            cmd_args.push("--no-visual".to_string()); // Default to no visual
+            self.play_matches(matches)?;
+
+            // Scores after this round decide the next pairing
+            self.update_player_stats();
         }
 
-        if let Some(random) = settings.random_assignment {
-            if random {
-                cmd_args.push("--random-assignment".to_string());
-            } else {
-                cmd_args.push("--no-random-assignment".to_string());
+        Ok(())
+    }
+
+    /// Swiss pairing input for one group, in the group's seed order.
+    fn swiss_entries(&self, group_name: &str, players: &[String]) -> Vec<swiss::SwissEntry> {
+        let mut entries: Vec<swiss::SwissEntry> = players.iter().map(|p| swiss::SwissEntry::new(p)).collect();
+        let group_stats = self.player_stats.get(&self.current_round).and_then(|round| round.get(group_name));
+
+        for entry in &mut entries {
+            if let Some(stats) = group_stats.and_then(|group| group.get(&entry.name)) {
+                entry.score = stats.points;
             }
+            entry.had_bye = self.byes.iter()
+                .any(|(round, group, player)| round == &self.current_round && group == group_name && player == &entry.name);
         }
 
-        if let Some(debug) = settings.debug {
-            if debug {
-                cmd_args.push("--debug".to_string());
-            } else {
-                cmd_args.push("--no-debug".to_string());
+        for result in self.round_results[&self.current_round].iter().filter(|r| r.group == group_name) {
+            for entry in &mut entries {
+                if entry.name == result.player1 {
+                    entry.opponents.insert(result.player2.clone());
+                    entry.color_balance += 1;
+                    entry.last_was_first = Some(true);
+                } else if entry.name == result.player2 {
+                    entry.opponents.insert(result.player1.clone());
+                    entry.color_balance -= 1;
+                    entry.last_was_first = Some(false);
+                }
             }
         }
 
-        if let Some(port) = settings.port {
-            cmd_args.push("--port".to_string());
-            cmd_args.push(port.to_string());
-        }
+        entries
+    }
 
-        // Log the command being executed
-        let cmd_str = format!("uv run cs4341-referee {}", cmd_args.join(" "));
-        debug!("Executing command: {}", cmd_str);
+    fn run_bracket(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let seeds = self.bracket_seeds.clone();
+        let format = self.current_stage().format;
 
-        // Run the command
-        let output = Command::new("uv")
-            .arg("run")
-            .arg("cs4341-referee")
-            .args(&cmd_args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?;
+        let play = |matches: Vec<bracket::BracketMatch>| self.play_series_round(&matches);
+
+        let ranking = if format == StageFormat::DoubleElimination {
+            bracket::double_elimination(&seeds, play)?
+        } else {
+            bracket::single_elimination(&seeds, play)?
+        };
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        self.bracket_rankings.insert(self.current_round.clone(), ranking);
 
-        // Log the output
-        debug!("Command stdout: {}", stdout);
-        if !stderr.is_empty() {
-            warn!("Command stderr: {}", stderr);
+        Ok(())
+    }
+
+    /// Play every best-of-N series of one bracket round and return the winners in order.
+    ///
+    /// Players alternate the first move, `player1` starting. Draws do not count
+    /// towards a series; if it is still level after twice the scheduled games,
+    /// `player1` (the better seed) goes through. The next game of every
+    /// unfinished series is played as one batch, so series run side by side.
+    fn play_series_round(&mut self, series: &[bracket::BracketMatch]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let best_of = self.current_stage().best_of();
+        let wins_needed = best_of / 2 + 1;
+        let max_games = 2 * best_of as i32;
+
+        for m in series {
+            self.groups.insert(m.label.clone(), vec![m.player1.clone(), m.player2.clone()]);
         }
 
-        // Parse result to determine winner
-        let mut result = self.parse_game_result(&stdout, &stderr, player1, player2);
+        let mut wins = vec![(0, 0); series.len()];
+        let mut game_number = 1;
+        loop {
+            let live: Vec<usize> = (0..series.len())
+                .filter(|&i| wins[i].0 < wins_needed && wins[i].1 < wins_needed)
+                .collect();
+            if live.is_empty() || game_number > max_games {
+                break;
+            }
 
-        // Set the group and game number in the result
-        result.group = group.to_string();
-        result.game_number = game_number;
+            let matches = live.iter()
+                .map(|&i| {
+                    let m = &series[i];
+                    let (first, second) = if game_number % 2 == 1 { (&m.player1, &m.player2) } else { (&m.player2, &m.player1) };
+                    ScheduledMatch::new(&m.label, game_number, first, second)
+                })
+                .collect();
+
+            for (&i, result) in live.iter().zip(self.play_matches(matches)?) {
+                match result.winner.as_deref() {
+                    Some(name) if name == series[i].player1 => wins[i].0 += 1,
+                    Some(name) if name == series[i].player2 => wins[i].1 += 1,
+                    _ => {}
+                }
+            }
+            game_number += 1;
+        }
 
-        info!("Match result: {} vs {} - Winner: {:?}, Draw: {}",
-              player1, player2, result.winner, result.is_draw);
+        let mut winners = Vec::new();
+        for (m, (p1_wins, p2_wins)) in series.iter().zip(wins) {
+            let (winner, loser) = if p2_wins > p1_wins { (&m.player2, &m.player1) } else { (&m.player1, &m.player2) };
+            info!("{}: {} beats {} ({}-{})", m.label, winner, loser, p1_wins.max(p2_wins), p1_wins.min(p2_wins));
+            winners.push(winner.clone());
+        }
 
-        Ok(result)
+        Ok(winners)
     }
 
-    fn parse_game_result(
-        &self,
-        stdout: &str,
-        stderr: &str,
-        player1: &str,
-        player2: &str,
-    ) -> MatchResult {
-        let mut result = MatchResult {
-            round: self.current_round.clone(),
-            group: String::new(), // Will be filled later
-            game_number: 0,       // Will be filled later
-            player1: player1.to_string(),
-            player2: player2.to_string(),
-            winner: None,
-            is_draw: false,
-            error: None,
+    /// Play off ties on the cut line of every group when the tiebreak chain
+    /// reaches `playoff` for them. Each playoff round the tied players meet
+    /// once, alternating the first move, until the tie is settled or
+    /// `playoff_games` rounds have been played.
+    fn play_playoffs(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let chain = self.tiebreak_chain();
+        let Some(position) = chain.iter().position(|&tiebreak| tiebreak == Tiebreak::Playoff) else {
+            return Ok(());
         };
+        let chain = chain[..=position].to_vec();
+        let rounds = self.config.settings.as_ref()
+            .and_then(|settings| settings.playoff_games)
+            .unwrap_or(DEFAULT_PLAYOFF_GAMES);
+
+        for playoff_round in 1..=rounds {
+            let matches = self.playoff_matches(&chain, playoff_round);
+            if matches.is_empty() {
+                break;
+            }
+            self.playoff_groups.extend(matches.iter().map(|scheduled| scheduled.group.clone()));
+            self.play_matches(matches)?;
+        }
+
+        Ok(())
+    }
 
-        // Check for errors
-        if !stderr.is_empty() && (stderr.contains("Error") || stderr.contains("error")) {
-            result.error = Some(stderr.to_string());
-            return result;
+    /// The games of one playoff round, ranking with `chain` (which ends in `playoff`).
+    fn playoff_matches(&self, chain: &[Tiebreak], playoff_round: usize) -> Vec<ScheduledMatch> {
+        let mut matches = Vec::new();
+        let Some(round_stats) = self.player_stats.get(&self.current_round) else {
+            return matches;
+        };
+        let tiebreaker = self.tiebreaker_with(chain);
+        let advance = self.current_stage().advance();
+
+        for (group_name, group_stats) in round_stats {
+            let tied: Vec<&String> = tiebreak::tied_at_cut(&tiebreaker.rank(group_stats.values()), advance).iter()
+                .map(|stats| &stats.name)
+                .collect();
+            if tied.is_empty() {
+                continue;
+            }
+
+            let playoff_group = stage::playoff_group_name(group_name);
+            let mut game_number = self.playoff_results.get(&self.current_round).into_iter().flatten()
+                .filter(|result| result.group == playoff_group)
+                .count() as i32 + 1;
+            info!("{}: playoff round {} between {}", group_name, playoff_round,
+                  tied.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(", "));
+
+            for i in 0..tied.len() {
+                for j in (i + 1)..tied.len() {
+                    let (player1, player2) = if playoff_round % 2 == 1 { (tied[i], tied[j]) } else { (tied[j], tied[i]) };
+                    matches.push(ScheduledMatch::new(&playoff_group, game_number, player1, player2));
+                    game_number += 1;
+                }
+            }
         }
 
-        // Look for game over message
-        if stdout.contains("Game over! Draw!") || stdout.contains("Game over! It's a draw!") {
-            result.is_draw = true;
-            return result;
+        matches
+    }
+
+    /// Play a batch of independent games and return their results in schedule order.
+    ///
+    /// Games already in the checkpoint are replayed; the rest are handed to a
+    /// pool of `self.jobs` worker threads. Results are recorded (CSV, standings,
+    /// checkpoint) strictly in schedule order as soon as every earlier game is
+    /// done, so the output does not depend on which game finishes first.
+    fn play_matches(&mut self, matches: Vec<ScheduledMatch>) -> Result<Vec<MatchResult>, Box<dyn std::error::Error>> {
+        let mut slots: Vec<Option<MatchResult>> = Vec::with_capacity(matches.len());
+        let mut pending = VecDeque::new();
+
+        for (index, scheduled) in matches.into_iter().enumerate() {
+            let key = (self.current_round.clone(), scheduled.group.clone(), scheduled.game_number);
+            match self.completed.remove(&key) {
+                Some(result) => {
+                    // Already played before the interruption; the schedule must line up
+                    if result.player1 != scheduled.player1 || result.player2 != scheduled.player2 {
+                        return Err(format!(
+                            "Checkpoint does not match the schedule at {} {} game {}: expected {} vs {}, found {} vs {}. \
+                             Was the config changed since the tournament started?",
+                            key.0, key.1, key.2, scheduled.player1, scheduled.player2, result.player1, result.player2
+                        ).into());
+                    }
+                    info!("Skipping {} vs {} ({} game {}), already played",
+                          scheduled.player1, scheduled.player2, scheduled.group, scheduled.game_number);
+                    slots.push(Some(result));
+                }
+                None => {
+                    slots.push(None);
+                    pending.push_back((index, scheduled));
+                }
+            }
+        }
+
+        let mut recorded = 0;
+        self.record_ready(&slots, &mut recorded)?;
+        if pending.is_empty() {
+            return Ok(slots.into_iter().flatten().collect());
         }
 
-        // Check for winner
-        if stdout.contains("Game over! Winner:") {
-            // For tictactoe
-            if stdout.contains("Winner: Player X") {
-                result.winner = Some(player1.to_string());
-            } else if stdout.contains("Winner: Player O") {
-                result.winner = Some(player2.to_string());
+        let runner = Arc::clone(&self.runner);
+        let workers = self.jobs.min(pending.len());
+        let round = self.current_round.clone();
+        let queue = Mutex::new(pending);
+        let mut failure: Option<Box<dyn std::error::Error>> = None;
+
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for _ in 0..workers {
+                let sender = sender.clone();
+                let (runner, queue, round) = (&*runner, &queue, &round);
+                scope.spawn(move || loop {
+                    let Some((index, scheduled)) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    info!("Match: {} vs {}", scheduled.player1, scheduled.player2);
+                    let result = runner.run_match(
+                        round, &scheduled.group, scheduled.game_number, &scheduled.player1, &scheduled.player2,
+                    );
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            for (index, result) in receiver {
+                if failure.is_some() {
+                    continue;
+                }
+                let outcome = result.map_err(Into::into).and_then(|result| {
+                    slots[index] = Some(result);
+                    self.record_ready(&slots, &mut recorded)
+                });
+                if let Err(e) = outcome {
+                    // Let the games already running finish, but start no new ones
+                    queue.lock().unwrap().clear();
+                    failure = Some(e);
+                }
             }
-            // For Lasker Morris
-            else if stdout.contains("Winner: blue") {
-                result.winner = Some(player1.to_string());
-            } else if stdout.contains("Winner: orange") {
-                result.winner = Some(player2.to_string());
+        });
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(slots.into_iter().flatten().collect()),
+        }
+    }
+
+    /// Record every finished game from `recorded` onwards up to the first one
+    /// still being played.
+    fn record_ready(&mut self, slots: &[Option<MatchResult>], recorded: &mut usize) -> Result<(), Box<dyn std::error::Error>> {
+        while let Some(Some(result)) = slots.get(*recorded) {
+            self.record_result(result)?;
+            self.played.push(result.clone());
+            let results = if self.playoff_groups.contains(&result.group) {
+                &mut self.playoff_results
+            } else {
+                &mut self.round_results
+            };
+            results.entry(self.current_round.clone()).or_default().push(result.clone());
+            if let Some(position) = self.resume_point.take_if(|position| position.games == self.played.len()) {
+                position.check(self.stage_index, &self.current_round, &self.groups, self.completed.len())?;
             }
+            self.save_checkpoint()?;
+            *recorded += 1;
         }
+        Ok(())
+    }
+
+    fn output(&self) -> OutputConfig {
+        self.config.output.clone().unwrap_or_default()
+    }
 
-        result
+    fn save_checkpoint(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // Results still waiting to be replayed stay in the checkpoint too
+        let mut results = self.played.clone();
+        let mut pending: Vec<&MatchResult> = self.completed.values().collect();
+        pending.sort_by(|a, b| (&a.round, &a.group, a.game_number).cmp(&(&b.round, &b.group, b.game_number)));
+        results.extend(pending.into_iter().cloned());
+
+        // Until every saved game is replayed, the saved position still applies
+        let (stage_index, round, groups) = match &self.resume_point {
+            Some(position) => (position.stage_index, position.round.clone(), position.groups.clone()),
+            None => (self.stage_index, self.current_round.clone(), self.groups.clone()),
+        };
+        let checkpoint = Checkpoint { seed: self.seed, stage_index, round, groups, results };
+        checkpoint.save(&self.output().checkpoint_path())
     }
 
     fn record_result(&mut self, result: &MatchResult) -> Result<(), Box<dyn std::error::Error>> {
@@ -431,46 +993,77 @@ impl TournamentManager {
     }
 
     fn update_player_stats(&mut self) {
+        // Recompute from scratch so this can run after every Swiss round
+        self.reset_round_stats();
+        let scoring = self.scoring();
+
         if let Some(results) = self.round_results.get(&self.current_round) {
-            let round_stats = self.player_stats.entry(self.current_round.clone())
-                                             .or_insert_with(HashMap::new);
+            let round_stats = self.player_stats.entry(self.current_round.clone()).or_default();
 
             for result in results {
                 let group_name = result.group.clone();
-                let group_stats = round_stats.entry(group_name).or_insert_with(HashMap::new);
+                let group_stats = round_stats.entry(group_name).or_default();
 
                 // Update player1 stats if in this group
                 if let Some(player1_stats) = group_stats.get_mut(&result.player1) {
-                    player1_stats.add_result(result);
+                    player1_stats.add_result(result, &scoring);
                 }
 
                 // Update player2 stats if in this group
                 if let Some(player2_stats) = group_stats.get_mut(&result.player2) {
-                    player2_stats.add_result(result);
+                    player2_stats.add_result(result, &scoring);
+                }
+            }
+
+            for (round, group, player) in &self.byes {
+                if round == &self.current_round {
+                    if let Some(stats) = round_stats.get_mut(group).and_then(|group_stats| group_stats.get_mut(player)) {
+                        stats.add_bye(&scoring);
+                    }
                 }
             }
         }
     }
 
-    fn determine_winners(&self) -> HashMap<String, Vec<String>> {
-        let mut winners = HashMap::new();
+    fn scoring(&self) -> Scoring {
+        self.config.scoring.unwrap_or_default()
+    }
 
-        if let Some(round_stats) = self.player_stats.get(&self.current_round) {
-            for (group_name, group_stats) in round_stats {
-                // Convert HashMap to Vec for sorting
-                let mut players: Vec<(String, &PlayerStats)> = group_stats.iter()
-                    .map(|(name, stats)| (name.clone(), stats))
-                    .collect();
+    fn tiebreak_chain(&self) -> &[Tiebreak] {
+        self.config.settings.as_ref()
+            .and_then(|settings| settings.tiebreaks.as_deref())
+            .unwrap_or(tiebreak::DEFAULT_CHAIN)
+    }
+
+    /// Orders the players of the current round who are level on points.
+    fn tiebreaker(&self) -> Tiebreaker<'_> {
+        self.tiebreaker_with(self.tiebreak_chain())
+    }
+
+    fn tiebreaker_with<'a>(&'a self, chain: &'a [Tiebreak]) -> Tiebreaker<'a> {
+        let players = self.player_stats.get(&self.current_round).into_iter()
+            .flat_map(|round_stats| round_stats.values())
+            .flat_map(|group_stats| group_stats.values());
+        let games = self.round_results.get(&self.current_round).map(Vec::as_slice).unwrap_or_default();
+        let playoffs = self.playoff_results.get(&self.current_round).map(Vec::as_slice).unwrap_or_default();
+        Tiebreaker::new(chain, self.scoring(), &self.current_round, players, games, playoffs, self.seed)
+    }
 
-                // Sort by points (descending)
-                players.sort_by(|a, b| b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal));
+    /// Standings of one group, best player first.
+    fn sorted_standings<'a>(&'a self, group_stats: &'a BTreeMap<String, PlayerStats>) -> Vec<Standing<'a>> {
+        self.tiebreaker().rank(group_stats.values())
+    }
 
-                // Take the top 2 players from each group
-                let num_to_advance = 2;
+    fn determine_winners(&self) -> BTreeMap<String, Vec<String>> {
+        let mut winners = BTreeMap::new();
+        let num_to_advance = self.current_stage().advance();
 
-                let group_winners: Vec<String> = players.iter()
+        if let Some(round_stats) = self.player_stats.get(&self.current_round) {
+            for (group_name, group_stats) in round_stats {
+                // Take the top players from each group
+                let group_winners: Vec<String> = self.sorted_standings(group_stats).iter()
                     .take(num_to_advance)
-                    .map(|(name, _)| name.clone())
+                    .map(|standing| standing.stats.name.clone())
                     .collect();
 
                 winners.insert(group_name.clone(), group_winners);
@@ -480,74 +1073,151 @@ impl TournamentManager {
         winners
     }
 
-    fn determine_fourth_round_rankings(&self) -> Vec<String> {
+    /// Ranking of every player in the current stage, best first.
+    ///
+    /// Placement stages rank by match (Championship winner, runner-up, third
+    /// place winner, ...). Group stages list all group winners, then all
+    /// runners-up and so on, each tier ordered by points and the tiebreakers.
+    fn determine_stage_rankings(&self) -> Vec<String> {
         let mut ranked_players = Vec::new();
 
-        if let Some(round_stats) = self.player_stats.get("Fourth Round") {
-            // Get the first (and only) group in the fourth round
-            if let Some((_, group_stats)) = round_stats.iter().next() {
-                // Convert HashMap to Vec for sorting
-                let mut players: Vec<(String, &PlayerStats)> = group_stats.iter()
-                    .map(|(name, stats)| (name.clone(), stats))
-                    .collect();
-
-                // Sort by points (descending)
-                players.sort_by(|a, b| b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal));
+        let Some(round_stats) = self.player_stats.get(&self.current_round) else {
+            return ranked_players;
+        };
 
-                // Return all players in ranked order
-                ranked_players = players.into_iter()
-                    .map(|(name, _)| name)
+        match self.current_stage().format {
+            StageFormat::SingleElimination | StageFormat::DoubleElimination => {
+                ranked_players = self.bracket_rankings.get(&self.current_round).cloned().unwrap_or_default();
+            }
+            StageFormat::Placement => {
+                let mut index = 0;
+                while let Some(group_stats) = round_stats.get(&stage::placement_group_name(index)) {
+                    ranked_players.extend(self.sorted_standings(group_stats).into_iter().map(|standing| standing.stats.name.clone()));
+                    index += 1;
+                }
+            }
+            StageFormat::RoundRobin | StageFormat::Swiss => {
+                let standings: Vec<Vec<Standing>> = round_stats.values()
+                    .map(|group_stats| self.sorted_standings(group_stats))
                     .collect();
+                let largest_group = standings.iter().map(Vec::len).max().unwrap_or(0);
+
+                for position in 0..largest_group {
+                    let tier = standings.iter().filter_map(|group| group.get(position)).map(|standing| standing.stats);
+                    ranked_players.extend(self.tiebreaker().rank(tier).into_iter().map(|standing| standing.stats.name.clone()));
+                }
             }
         }
 
         ranked_players
     }
 
-    fn setup_next_round(
-        &mut self,
-        winners: &HashMap<String, Vec<String>>,
-        num_groups: usize,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Flatten all winners
-        let mut all_winners = Vec::new();
-        for group_winners in winners.values() {
-            all_winners.extend(group_winners.clone());
+    /// Players moving on from the current stage, best seed first.
+    fn determine_advancing(&self) -> Vec<String> {
+        let stage = self.current_stage();
+        match stage.format {
+            StageFormat::Placement | StageFormat::SingleElimination | StageFormat::DoubleElimination => {
+                self.determine_stage_rankings().into_iter().take(stage.advance()).collect()
+            }
+            StageFormat::RoundRobin | StageFormat::Swiss => {
+                let winners = self.determine_winners();
+                self.determine_stage_rankings().into_iter()
+                    .filter(|player| winners.values().any(|group| group.contains(player)))
+                    .collect()
+            }
         }
+    }
 
-        // Shuffle winners for random assignment
-        let mut rng = thread_rng();
-        all_winners.shuffle(&mut rng);
+    /// Replace `self.groups` with `players` split according to the stage's group count.
+    fn assign_groups(&mut self, players: &[String], stage: &StageConfig, seeding: stage::Seeding) {
+        let groups = stage::distribute(players, stage.groups, seeding, &mut self.rng);
 
-        // Create new groups
         self.groups.clear();
-        let players_per_group = all_winners.len() / num_groups;
+        for (i, group_players) in groups.into_iter().enumerate() {
+            if !group_players.is_empty() {
+                self.groups.insert(stage::group_name(i), group_players);
+            }
+        }
+    }
 
-        for i in 0..num_groups {
-            let start = i * players_per_group;
-            let end = if i == num_groups - 1 {
-                all_winners.len()
-            } else {
-                start + players_per_group
-            };
+    /// Group winners of the previous stage when `stage` keeps them apart.
+    /// Called before the new groups are drawn, while `self.groups` still
+    /// holds the previous stage's groups; `entrants` list every group winner
+    /// first.
+    fn previous_group_winners(&self, entrants: &[String], stage: &StageConfig) -> HashSet<String> {
+        let previous = &self.stages[self.stage_index - 1];
+        let grouped = previous.format.is_grouped();
+        if !stage.separate_winners() || !grouped {
+            return HashSet::new();
+        }
+        entrants.iter().take(self.groups.len()).cloned().collect()
+    }
 
-            let group_name = format!("Group {}", (b'A' + i as u8) as char);
+    /// Rearrange the freshly drawn groups so players who have already met,
+    /// and the previous stage's group winners, end up apart where possible.
+    /// Seeded draws only swap players within a pot to keep the groups
+    /// balanced.
+    fn keep_apart(&mut self, ranking: &[String], stage: &StageConfig, winners: &HashSet<String>) {
+        let met: HashSet<(String, String)> = if stage.avoid_rematches() {
+            self.played.iter()
+                .map(|game| {
+                    let (a, b) = (game.player1.clone(), game.player2.clone());
+                    if a < b { (a, b) } else { (b, a) }
+                })
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        if met.is_empty() && winners.is_empty() {
+            return;
+        }
 
-            // Make sure we don't go out of bounds
-            if start < all_winners.len() {
-                let end_idx = std::cmp::min(end, all_winners.len());
-                let group_players = all_winners[start..end_idx].to_vec();
+        let pot_of: HashMap<String, usize> = match stage.seeding {
+            stage::Seeding::Random => HashMap::new(),
+            _ => ranking.iter().enumerate().map(|(i, player)| (player.clone(), i / self.groups.len().max(1))).collect(),
+        };
+        let mut groups: Vec<Vec<String>> = self.groups.values().cloned().collect();
+        let clashes = stage::keep_apart(&mut groups, &pot_of, &met, winners);
+        for (players, regrouped) in self.groups.values_mut().zip(groups) {
+            *players = regrouped;
+        }
 
-                if !group_players.is_empty() {
-                    self.groups.insert(group_name, group_players);
-                }
-            }
+        let describe = |pairs: &[(String, String)]| {
+            pairs.iter().map(|(a, b)| format!("{} vs {}", a, b)).collect::<Vec<_>>().join(", ")
+        };
+        if !clashes.rematches.is_empty() {
+            warn!("Could not avoid {} rematch(es) in {}: {}", clashes.rematches.len(), stage.name, describe(&clashes.rematches));
         }
+        if !clashes.together.is_empty() {
+            warn!("Could not keep every group winner apart in {}: {}", stage.name, describe(&clashes.together));
+        }
+    }
 
+    /// Print a seeded draw: the ranking split into pots (one player per group,
+    /// in ranking order), then the groups it produced.
+    fn print_draw(&self, ranking: &[String], stage: &StageConfig, seeding: stage::Seeding) {
+        let method = match seeding {
+            stage::Seeding::Pots => "pots",
+            _ => "snake order",
+        };
+        println!("\n=== DRAW FOR {} ({}) ===\n", stage.name.to_uppercase(), method);
+        info!("Draw for {} ({}):", stage.name, method);
+
+        for (i, pot) in ranking.chunks(self.groups.len().max(1)).enumerate() {
+            println!("Pot {}: {}", i + 1, pot.join(", "));
+            info!("Pot {}: {}", i + 1, pot.join(", "));
+        }
+        println!();
+        for (group, players) in &self.groups {
+            println!("{}: {}", group, players.join(", "));
+        }
+    }
+
+    fn reset_round_stats(&mut self) {
         // Initialize player stats for this round
-        let mut round_stats = HashMap::new();
+        let mut round_stats = BTreeMap::new();
         for (group, players) in &self.groups {
-            let mut group_stats = HashMap::new();
+            let mut group_stats = BTreeMap::new();
             for player in players {
                 let player_stats = PlayerStats::new(player);
                 group_stats.insert(player.clone(), player_stats);
@@ -555,6 +1225,32 @@ impl TournamentManager {
             round_stats.insert(group.clone(), group_stats);
         }
         self.player_stats.insert(self.current_round.clone(), round_stats);
+    }
+
+    fn setup_next_round(&mut self, entrants: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let stage = self.current_stage().clone();
+
+        if entrants.is_empty() {
+            return Err(format!("No players advanced into {}", stage.name).into());
+        }
+
+        match stage.format {
+            StageFormat::Placement => return self.setup_finals(entrants),
+            StageFormat::SingleElimination | StageFormat::DoubleElimination => {
+                self.setup_bracket(entrants);
+                return Ok(());
+            }
+            StageFormat::RoundRobin | StageFormat::Swiss => {
+                let winners = self.previous_group_winners(entrants, &stage);
+                self.assign_groups(entrants, &stage, stage.seeding);
+                self.keep_apart(entrants, &stage, &winners);
+                if stage.seeding != stage::Seeding::Random {
+                    self.print_draw(entrants, &stage, stage.seeding);
+                }
+            }
+        }
+
+        self.reset_round_stats();
 
         // Log the new groups
         info!("New groups for {}:", self.current_round);
@@ -565,57 +1261,40 @@ impl TournamentManager {
         Ok(())
     }
 
+    /// Bracket groups are created match by match while the bracket is played.
+    fn setup_bracket(&mut self, seeds: &[String]) {
+        self.bracket_seeds = seeds.to_vec();
+        self.groups.clear();
+        self.reset_round_stats();
+
+        info!("Bracket seeds for {}:", self.current_round);
+        for (i, player) in seeds.iter().enumerate() {
+            info!("{}. {}", i + 1, player);
+        }
+    }
+
     fn setup_finals(&mut self, ranked_players: &[String]) -> Result<(), Box<dyn std::error::Error>> {
         // Clear old groups
         self.groups.clear();
 
-        // We need at least 4 players for the final setup
-        if ranked_players.len() >= 4 {
-            // Top 2 play for 1st/2nd place
-            self.groups.insert(
-                "Championship".to_string(),
-                vec![ranked_players[0].clone(), ranked_players[1].clone()]
-            );
-
-            // 3rd and 4th play for 3rd/4th place
-            self.groups.insert(
-                "Third Place Match".to_string(),
-                vec![ranked_players[2].clone(), ranked_players[3].clone()]
-            );
-        } else {
-            warn!("Not enough players from Fourth Round for proper finals. Setting up with available players.");
-            if ranked_players.len() >= 2 {
-                self.groups.insert(
-                    "Championship".to_string(),
-                    vec![ranked_players[0].clone(), ranked_players[1].clone()]
-                );
+        // Pair neighbouring seeds: 1st vs 2nd, 3rd vs 4th, ...
+        for (index, pair) in ranked_players.chunks(2).enumerate() {
+            if pair.len() < 2 {
+                warn!("{} has no opponent in {}; they keep their seed", pair[0], self.current_round);
             }
+            self.groups.insert(stage::placement_group_name(index), pair.to_vec());
         }
 
-        // Initialize player stats for this round
-        let mut round_stats = HashMap::new();
-        for (group, players) in &self.groups {
-            let mut group_stats = HashMap::new();
-            for player in players {
-                let player_stats = PlayerStats::new(player);
-                group_stats.insert(player.clone(), player_stats);
-            }
-            round_stats.insert(group.clone(), group_stats);
-        }
-        self.player_stats.insert(self.current_round.clone(), round_stats);
+        self.reset_round_stats();
 
         // Log finals setup
         info!("Finals setup:");
-        if let Some(championship_players) = self.groups.get("Championship") {
-            if championship_players.len() >= 2 {
-                info!("Championship match: {} vs {}", championship_players[0], championship_players[1]);
-            }
-        }
-
-        if let Some(third_place_players) = self.groups.get("Third Place Match") {
-            if third_place_players.len() >= 2 {
-                info!("Third place match: {} vs {}", third_place_players[0], third_place_players[1]);
+        let mut index = 0;
+        while let Some(players) = self.groups.get(&stage::placement_group_name(index)) {
+            if players.len() >= 2 {
+                info!("{}: {} vs {}", stage::placement_group_name(index), players[0], players[1]);
             }
+            index += 1;
         }
 
         Ok(())
@@ -624,39 +1303,51 @@ impl TournamentManager {
     fn print_standings(&self) {
         println!("\n=== Current Standings ({}) ===", self.current_round);
 
-        if let Some(round_stats) = self.player_stats.get(&self.current_round) {
+        if self.current_stage().format.is_elimination() {
+            self.print_bracket_results();
+        } else if let Some(round_stats) = self.player_stats.get(&self.current_round) {
             for (group_name, group_stats) in round_stats {
                 println!("\n{}:", group_name);
 
-                // Convert HashMap to Vec for sorting
-                let mut players: Vec<(String, &PlayerStats)> = group_stats.iter()
-                    .map(|(name, stats)| (name.clone(), stats))
-                    .collect();
-
-                players.sort_by(|a, b| b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal));
+                println!("{:<20} {:<5} {:<5} {:<5} {:<5} {:<5} {:<16}", "Player", "W", "L", "D", "F", "Pts", "Tiebreak");
+                println!("{}", "-".repeat(63));
 
-                println!("{:<20} {:<5} {:<5} {:<5} {:<5}", "Player", "W", "L", "D", "Pts");
-                println!("{}", "-".repeat(40));
+                for Standing { stats, decided_by } in self.sorted_standings(group_stats) {
+                    let tiebreak = decided_by.map(Tiebreak::label).unwrap_or_default();
+                    println!("{:<20} {:<5} {:<5} {:<5} {:<5} {:<5.1} {}",
+                            stats.name, stats.wins, stats.losses, stats.draws, stats.forfeits, stats.points, tiebreak);
+                }
 
-                for (name, stats) in players {
-                    println!("{:<20} {:<5} {:<5} {:<5} {:<5.1}",
-                            name, stats.wins, stats.losses, stats.draws, stats.points);
+                let playoff_group = stage::playoff_group_name(group_name);
+                for result in self.playoff_results.get(&self.current_round).into_iter().flatten()
+                    .filter(|result| result.group == playoff_group)
+                {
+                    let outcome = match (&result.winner, result.is_draw) {
+                        (Some(winner), _) => format!("{} wins", winner),
+                        (None, true) => "draw".to_string(),
+                        (None, false) => "no result".to_string(),
+                    };
+                    println!("{} game {}: {} vs {}, {}", playoff_group, result.game_number, result.player1, result.player2, outcome);
                 }
             }
         }
 
-        // If this is the end of a round, show who advances
-        if self.current_round == "First Round" || self.current_round == "Second Round" ||
-           self.current_round == "Third Round" || self.current_round == "Fourth Round" {
-
-            if self.current_round == "Fourth Round" {
-                let ranked_players = self.determine_fourth_round_rankings();
-                println!("\nFinal ranking from Fourth Round:");
+        // If another stage follows, show who advances
+        if let Some(next_stage) = self.stages.get(self.stage_index + 1) {
+            let grouped = self.current_stage().format.is_grouped();
+            if next_stage.format == StageFormat::Placement || next_stage.format.is_elimination() || !grouped {
+                let ranked_players = self.determine_advancing();
+                println!("\nFinal ranking from {}:", self.current_round);
                 for (i, player) in ranked_players.iter().enumerate() {
                     println!("{}. {}", i + 1, player);
                 }
-                println!("\nAdvancing to Championship: {} and {}", ranked_players[0], ranked_players[1]);
-                println!("Playing for 3rd place: {} and {}", ranked_players[2], ranked_players[3]);
+                if next_stage.format == StageFormat::Placement {
+                    for (index, pair) in ranked_players.chunks(2).enumerate() {
+                        if pair.len() == 2 {
+                            println!("{}: {} and {}", stage::placement_group_name(index), pair[0], pair[1]);
+                        }
+                    }
+                }
             } else {
                 let winners = self.determine_winners();
                 println!("\nAdvancing to next round:");
@@ -669,65 +1360,59 @@ impl TournamentManager {
         println!();
     }
 
-    fn print_final_results(&self) {
-        println!("\n=== TOURNAMENT FINAL RESULTS ===\n");
-
-        if let Some(final_stats) = self.player_stats.get("Final Round") {
-            // Championship result
-            if let Some(championship_stats) = final_stats.get("Championship") {
-                // Convert HashMap to Vec for sorting
-                let mut finalists: Vec<(String, &PlayerStats)> = championship_stats.iter()
-                    .map(|(name, stats)| (name.clone(), stats))
-                    .collect();
-
-                finalists.sort_by(|a, b| b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal));
+    /// One line per bracket series, in the order they were played.
+    fn print_bracket_results(&self) {
+        let Some(round_stats) = self.player_stats.get(&self.current_round) else {
+            return;
+        };
 
-                if finalists.len() >= 2 {
-                    println!("🏆 CHAMPION: {}", finalists[0].0);
-                    println!("🥈 RUNNER-UP: {}", finalists[1].0);
-                } else if !finalists.is_empty() {
-                    println!("🏆 CHAMPION: {}", finalists[0].0);
-                }
+        let mut labels: Vec<&String> = Vec::new();
+        for result in self.round_results.get(&self.current_round).into_iter().flatten() {
+            if !labels.contains(&&result.group) {
+                labels.push(&result.group);
             }
+        }
 
-            // Third place result
-            if let Some(third_place_stats) = final_stats.get("Third Place Match") {
-                // Convert HashMap to Vec for sorting
-                let mut third_place_contestants: Vec<(String, &PlayerStats)> = third_place_stats.iter()
-                    .map(|(name, stats)| (name.clone(), stats))
-                    .collect();
+        println!();
+        for label in labels {
+            let (Some(players), Some(group_stats)) = (self.groups.get(label), round_stats.get(label)) else {
+                continue;
+            };
+            let wins = |player: &String| group_stats.get(player).map_or(0, |stats| stats.wins);
+            println!("{:<32} {} {}-{} {}", label, players[0], wins(&players[0]), wins(&players[1]), players[1]);
+        }
+    }
 
-                third_place_contestants.sort_by(|a, b| {
-                    b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal)
-                });
+    fn print_final_results(&self) {
+        println!("\n=== TOURNAMENT FINAL RESULTS ===\n");
+        println!("Random seed: {} (rerun with --random-seed {})\n", self.seed, self.seed);
 
-                if third_place_contestants.len() >= 2 {
-                    println!("🥉 THIRD PLACE: {}", third_place_contestants[0].0);
-                    println!("    FOURTH PLACE: {}", third_place_contestants[1].0);
-                } else if !third_place_contestants.is_empty() {
-                    println!("🥉 THIRD PLACE: {}", third_place_contestants[0].0);
-                }
-            }
+        // The last stage decides the final places
+        let ranked_players = self.determine_stage_rankings();
+        let labels = ["🏆 CHAMPION", "🥈 RUNNER-UP", "🥉 THIRD PLACE", "    FOURTH PLACE"];
+        for (label, player) in labels.iter().zip(&ranked_players) {
+            println!("{}: {}", label, player);
         }
 
-        println!("\nTournament completed! Full results saved in tournament_results.csv");
+        println!("\nTournament completed! Full results saved in {}", self.output().results_path());
     }
 
     fn print_overall_stats(&self) {
         println!("\n=== OVERALL TOURNAMENT STATISTICS ===\n");
 
         // Create a map to track overall player performance
-        let mut overall_stats = HashMap::new();
+        let mut overall_stats = BTreeMap::new();
 
         // Collect stats from all rounds
-        for (_, round_stats) in &self.player_stats {
-            for (_, group_stats) in round_stats {
+        for round_stats in self.player_stats.values() {
+            for group_stats in round_stats.values() {
                 for (player_name, stats) in group_stats {
                     let entry = overall_stats.entry(player_name.clone())
                                           .or_insert_with(|| PlayerStats::new(player_name));
                     entry.wins += stats.wins;
                     entry.losses += stats.losses;
                     entry.draws += stats.draws;
+                    entry.forfeits += stats.forfeits;
                     entry.points += stats.points;
                 }
             }
@@ -739,18 +1424,80 @@ impl TournamentManager {
             .collect();
         players.sort_by(|a, b| b.1.points.partial_cmp(&a.1.points).unwrap_or(std::cmp::Ordering::Equal));
 
-        println!("{:<20} {:<5} {:<5} {:<5} {:<5}", "Player", "W", "L", "D", "Pts");
-        println!("{}", "-".repeat(40));
+        println!("{:<20} {:<5} {:<5} {:<5} {:<5} {:<5}", "Player", "W", "L", "D", "F", "Pts");
+        println!("{}", "-".repeat(46));
 
         for (name, stats) in players {
-            println!("{:<20} {:<5} {:<5} {:<5} {:<5.1}",
-                   name, stats.wins, stats.losses, stats.draws, stats.points);
+            println!("{:<20} {:<5} {:<5} {:<5} {:<5} {:<5.1}",
+                   name, stats.wins, stats.losses, stats.draws, stats.forfeits, stats.points);
+        }
+    }
+
+    /// Print the rating of every agent over all games of the tournament and
+    /// export it.
+    fn report_ratings(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.config.ratings.unwrap_or_default();
+        let players: Vec<String> = self.config.agents.keys().cloned().collect();
+        let ratings = rating::rate(&players, &self.played, &config);
+
+        println!("\n=== RATINGS ({}) ===\n", config.describe());
+        if config.model == RatingModel::Elo {
+            println!("{:<5} {:<20} {:<8} {:<5}", "Rank", "Player", "Rating", "Games");
+            println!("{}", "-".repeat(41));
+            for rating in &ratings {
+                println!("{:<5} {:<20} {:<8.1} {:<5}", rating.rank, rating.player, rating.rating, rating.games);
+            }
+        } else {
+            println!("{:<5} {:<20} {:<8} {:<17} {:<5} {:<13}", "Rank", "Player", "Rating", "95% interval", "Games", "Clear of next");
+            println!("{}", "-".repeat(73));
+            for (index, rating) in ratings.iter().enumerate() {
+                let (low, high) = rating.interval().unwrap_or_default();
+                let clear = match ratings.get(index + 1).and_then(|next| rating.clear_of(next)) {
+                    Some(true) => "yes",
+                    Some(false) => "no",
+                    None => "",
+                };
+                println!("{:<5} {:<20} {:<8.1} {:<17} {:<5} {}",
+                         rating.rank, rating.player, rating.rating, format!("{:.1} - {:.1}", low, high), rating.games, clear);
+            }
+            println!("\nAgents whose intervals overlap cannot be told apart with 95% confidence.");
+        }
+
+        if let Some(path) = self.output().ratings_path() {
+            rating::export(&path, &ratings)?;
+            println!("\nRatings saved in {}", path);
+        }
+        Ok(())
+    }
+
+    /// Add this tournament's games to the ladder and print the leaderboard.
+    fn update_ladder(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(config) = &self.config.ladder else {
+            return Ok(());
+        };
+
+        let mut ladder = Ladder::load(&config.path)?;
+        let name = config.event.clone().unwrap_or_else(|| format!("Tournament {}", self.seed));
+        let replaced = ladder.record(ladder::Event {
+            name: name.clone(),
+            seed: self.seed,
+            agents: self.config.agents.keys().cloned().collect(),
+            results: self.played.clone(),
+        });
+        ladder.save(&config.path)?;
+        if replaced {
+            info!("Replaced the earlier results of {} on the ladder", name);
         }
+
+        ladder::print(&ladder, &self.config.ratings.unwrap_or_default());
+        println!("\n{} saved to the ladder in {}", name, config.path);
+        Ok(())
     }
 
     fn write_csv_header(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.csv_writer.write_record(&[
-            "Round", "Group", "Game Number", "Player 1", "Player 2", "Winner", "Is Draw", "Error"
+        self.csv_writer.write_record([
+            "Round", "Group", "Game Number", "Player 1", "Player 2", "Player 1 Color", "Player 2 Color", "Winner", "Is Draw", "Forfeit",
+            "Termination", "Moves", "Player 1 Time", "Player 2 Time", "Error", "Transcript"
         ])?;
         self.csv_writer.flush()?;
         Ok(())
@@ -760,37 +1507,193 @@ impl TournamentManager {
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command line arguments
     let args: Vec<String> = std::env::args().collect();
+    let options = cli::parse_args(&args)?;
 
-    // Check for logging level
-    let log_level = if args.iter().any(|arg| arg == "--quiet" || arg == "-q") {
-        LevelFilter::Error // Only show errors
-    } else if args.iter().any(|arg| arg == "--debug" || arg == "-d") {
-        LevelFilter::Debug // Show debug and above
-    } else if args.iter().any(|arg| arg == "--no-log") {
-        LevelFilter::Off // Turn off logging completely
-    } else {
-        LevelFilter::Info // Default: Show info and above
+    if let cli::Command::Replay { transcript } = &options.command {
+        if let Err(e) = replay::run(transcript, options.replay_move) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Check the whole config up front so problems surface before any game is played
+    let config = match validate::load(&options.config_path, !options.skip_validation) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
     };
+    if options.command == cli::Command::Validate {
+        println!("{} is valid ({} agents)", options.config_path, config.agents.len());
+        return Ok(());
+    }
+    if options.command == cli::Command::Ladder {
+        let Some(ladder) = &config.ladder else {
+            eprintln!("{} has no [ladder] section", options.config_path);
+            std::process::exit(1);
+        };
+        ladder::print(&Ladder::load(&ladder.path)?, &config.ratings.unwrap_or_default());
+        return Ok(());
+    }
 
-    // Initialize logging with selected level
-    let log_file = File::create("tournament.log")?;
-    WriteLogger::init(log_level, Config::default(), log_file)?;
+    // Initialize logging with selected level; a resumed run keeps the old log
+    let log_file = if options.resume {
+        fs::OpenOptions::new().create(true).append(true).open("tournament.log")?
+    } else {
+        File::create("tournament.log")?
+    };
+    WriteLogger::init(options.log_level, Config::default(), log_file)?;
 
     info!("Starting tournament manager");
-
-    // Get config path from args
-    let config_path = args.iter()
-        .find(|arg| !arg.starts_with("-") && !arg.starts_with("--") && **arg != args[0])
-        .map(|s| s.as_str())
-        .unwrap_or("tournament.toml");
-
-    info!("Using config file: {}", config_path);
+    info!("Using config file: {}", options.config_path);
 
     // Initialize and run the tournament
-    let mut tournament = TournamentManager::new(config_path)?;
+    let mut tournament = TournamentManager::new(config, &options)?;
     tournament.run_tournament()?;
 
     info!("Tournament completed successfully");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    /// (round, group, game number, player1, player2) of a game.
+    type GameKey = (String, String, i32, String, String);
+
+    fn key(result: &MatchResult) -> GameKey {
+        (result.round.clone(), result.group.clone(), result.game_number, result.player1.clone(), result.player2.clone())
+    }
+
+    /// Plays games without starting any agent: the name that sorts first wins.
+    /// Of every three games started, the earlier ones take longer.
+    #[derive(Debug, Default)]
+    struct FakeRunner {
+        started: Mutex<Vec<GameKey>>,
+        finished: Mutex<Vec<GameKey>>,
+    }
+
+    impl PlayGame for FakeRunner {
+        fn run_match(&self, round: &str, group: &str, game_number: i32, player1: &str, player2: &str) -> Result<MatchResult, String> {
+            let mut result = MatchResult::test(player1, player2, Some(player1.min(player2)));
+            result.round = round.to_string();
+            result.group = group.to_string();
+            result.game_number = game_number;
+            let index = {
+                let mut started = self.started.lock().unwrap();
+                started.push(key(&result));
+                started.len() - 1
+            };
+            thread::sleep(Duration::from_millis(20 * (2 - index % 3) as u64));
+            self.finished.lock().unwrap().push(key(&result));
+            Ok(result)
+        }
+    }
+
+    /// A tournament of `agents` that writes its files to a fresh directory.
+    fn manager(name: &str, agents: &[&str], settings: &str, stages: &str) -> (TournamentManager, Arc<FakeRunner>, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("tournament_test_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let agents: String = agents.iter().map(|agent| format!("{} = \"{}.py\"\n", agent, agent)).collect();
+        let config = format!(
+            "game = \"tictactoe\"\n[settings]\n{}\n[agents]\n{}\n{}\n[output]\nresults = {:?}\ncheckpoint = {:?}\ntranscripts = \"\"\nratings = \"\"\n",
+            settings, agents, stages, dir.join("results.csv"), dir.join("state.json"),
+        );
+        let options = cli::Options {
+            command: cli::Command::Run,
+            config_path: String::new(),
+            log_level: log::LevelFilter::Off,
+            resume: false,
+            random_seed: None,
+            jobs: None,
+            skip_validation: true,
+            replay_move: None,
+        };
+        let mut manager = TournamentManager::new(toml::from_str(&config).unwrap(), &options).unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        manager.runner = runner.clone();
+        (manager, runner, dir)
+    }
+
+    const POOLS_AND_FINAL: &str = "[[stages]]\nname = \"Pools\"\ngroups = 2\nadvance = 1\n\
+                                   [[stages]]\nname = \"Final\"\nformat = \"placement\"\n";
+
+    #[test]
+    fn parallel_games_are_recorded_in_schedule_order() {
+        let (mut manager, runner, dir) = manager("order", &["a", "b", "c", "d", "e", "f"], "seed = 3\nparallelism = 3", POOLS_AND_FINAL);
+        manager.run_tournament().unwrap();
+
+        let started = runner.started.lock().unwrap().clone();
+        assert_eq!(started.len(), 2 * 2 * 3 + 2);
+        assert_ne!(*runner.finished.lock().unwrap(), started, "the games should finish out of order");
+        assert_eq!(manager.played.iter().map(key).collect::<Vec<_>>(), started);
+
+        let rows: Vec<GameKey> = csv::Reader::from_path(dir.join("results.csv")).unwrap()
+            .records()
+            .map(|record| {
+                let record = record.unwrap();
+                (record[0].to_string(), record[1].to_string(), record[2].parse().unwrap(), record[3].to_string(), record[4].to_string())
+            })
+            .collect();
+        assert_eq!(rows, started);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn the_same_seed_draws_the_same_tournament() {
+        let agents = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let stages = "[[stages]]\nname = \"Pools\"\ngroups = 2\nadvance = 2\n\
+                      [[stages]]\nname = \"Swiss\"\nformat = \"swiss\"\nrounds = 2\n";
+        let play = |name: &str, seed: u64| {
+            let (mut manager, _, dir) = manager(name, &agents, &format!("seed = {}\nparallelism = 2", seed), stages);
+            manager.run_tournament().unwrap();
+            fs::remove_dir_all(dir).unwrap();
+            manager.played.iter().map(key).collect::<Vec<_>>()
+        };
+        // Who was drawn into which pool
+        let draw = |played: &[GameKey]| {
+            let mut pools = BTreeMap::<String, BTreeSet<String>>::new();
+            for (_, group, _, player1, player2) in played.iter().filter(|game| game.0 == "Pools") {
+                pools.entry(group.clone()).or_default().extend([player1.clone(), player2.clone()]);
+            }
+            pools.into_values().collect::<Vec<_>>()
+        };
+
+        let played = play("seed_first", 11);
+        let replayed = play("seed_second", 11);
+        assert_eq!(draw(&replayed), draw(&played));
+        assert_eq!(replayed, played);
+        assert_ne!(play("seed_other", 12), played);
+    }
+
+    #[test]
+    fn random_assignment_keeps_scheduled_colors() {
+        let agents = ["a", "b", "c", "d", "e", "f"];
+        let play = |name: &str, random: bool, stages: &str| {
+            let settings = format!("seed = 5\nrandom_assignment = {}", random);
+            let (mut manager, _, dir) = manager(name, &agents, &settings, stages);
+            manager.run_tournament().unwrap();
+            fs::remove_dir_all(dir).unwrap();
+            manager.played.iter().map(key).collect::<Vec<_>>()
+        };
+
+        // Swiss pairings and bracket series already decide who moves first
+        let stages = "[[stages]]\nname = \"Swiss\"\nformat = \"swiss\"\nrounds = 3\nadvance = 4\n\
+                      [[stages]]\nname = \"Knockout\"\nformat = \"single_elimination\"\nbest_of = 3\n";
+        assert_eq!(play("colors_random", true, stages), play("colors_fixed", false, stages));
+
+        // Round robin legs may swap, but each player still moves first once
+        let stages = "[[stages]]\nname = \"League\"\n";
+        let random = play("legs_random", true, stages);
+        assert_ne!(random, play("legs_fixed", false, stages));
+        for legs in random.chunks(2) {
+            assert_eq!((&legs[0].3, &legs[0].4), (&legs[1].4, &legs[1].3));
+        }
+    }
+}
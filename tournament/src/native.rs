@@ -0,0 +1,158 @@
+use log::{debug, warn};
+use std::time::Duration;
+
+use crate::agent::{Agent, ReadError};
+use crate::game::{Game, IllegalMove};
+use crate::runner::{MoveRecord, MoveTimings, RefereeResult};
+
+/// Play one game in-process instead of through the Python referee.
+pub fn play(game: &dyn Game, commands: [&str; 2], timeout: Duration) -> RefereeResult {
+    let recorder = Recorder::new(game.colors());
+    let mut agents = match start_agents(commands, game.initial_messages()) {
+        Ok(agents) => agents,
+        Err((slot, error)) => {
+            // An agent that cannot be started forfeits the game
+            warn!("{}", error);
+            let mut result = recorder.forfeit(slot, "crash");
+            result.error = Some(error);
+            return result;
+        }
+    };
+
+    let result = play_game(game, &mut agents, recorder, timeout + game.timeout_grace());
+    if let Some(message) = game.end_message() {
+        for agent in agents.iter_mut() {
+            agent.write(message);
+        }
+    }
+    stop_agents(&mut agents);
+    result
+}
+
+fn play_game(game: &dyn Game, agents: &mut [Agent; 2], mut recorder: Recorder, timeout: Duration) -> RefereeResult {
+    let mut state = game.new_state();
+
+    loop {
+        let slot = state.turn();
+        if let Some((winner, termination)) = state.before_move() {
+            return recorder.finish(winner, termination);
+        }
+
+        let (line, time) = agents[slot].read(timeout);
+        recorder.add_time(slot, time);
+        let record = |text: &str| MoveRecord {
+            player: slot_name(slot).to_string(),
+            text: text.to_string(),
+            time: Some(round_time(time.as_secs_f64())),
+        };
+
+        let played = match &line {
+            Ok(text) => state.play(text),
+            Err(error) => Err(IllegalMove { well_formed: false, reason: format!("{:?}", error) }),
+        };
+        let outcome = match played {
+            Ok(outcome) => outcome,
+            Err(illegal) => {
+                let reason = forfeit_reason(&mut agents[slot], &line, illegal.well_formed);
+                debug!("{} forfeits with {:?}: {} ({})", recorder.colors[slot], line, illegal.reason, reason);
+                recorder.rejected_move = line.as_deref().ok().map(record);
+                return recorder.forfeit(slot, reason);
+            }
+        };
+
+        let text = line.as_deref().unwrap_or_default();
+        recorder.history.push(record(text));
+        agents[1 - slot].write(text);
+
+        if let Some((winner, termination)) = outcome {
+            return recorder.finish(winner, termination);
+        }
+    }
+}
+
+pub fn slot_name(slot: usize) -> &'static str {
+    if slot == 0 { "player1" } else { "player2" }
+}
+
+/// Seconds rounded to milliseconds, as the Python referee reports them.
+fn round_time(seconds: f64) -> f64 {
+    (seconds * 1000.0).round() / 1000.0
+}
+
+/// Moves and thinking time of a game in progress.
+#[derive(Debug)]
+struct Recorder {
+    colors: [&'static str; 2],
+    history: Vec<MoveRecord>,
+    rejected_move: Option<MoveRecord>,
+    times: [f64; 2],
+}
+
+impl Recorder {
+    fn new(colors: [&'static str; 2]) -> Self {
+        Recorder { colors, history: Vec::new(), rejected_move: None, times: [0.0; 2] }
+    }
+
+    fn add_time(&mut self, slot: usize, time: Duration) {
+        self.times[slot] += time.as_secs_f64();
+    }
+
+    /// Report the end of the game; `winner` is a slot, `None` for a draw.
+    fn finish(self, winner: Option<usize>, termination: &str) -> RefereeResult {
+        RefereeResult {
+            winner: winner.map(|slot| slot_name(slot).to_string()),
+            termination: termination.to_string(),
+            moves: Some(self.history.len() as u32),
+            colors: (0..2).map(|slot| (slot_name(slot).to_string(), self.colors[slot].to_string())).collect(),
+            timings: (0..2)
+                .map(|slot| (slot_name(slot).to_string(), MoveTimings { total: round_time(self.times[slot]) }))
+                .collect(),
+            error: None,
+            history: self.history,
+            rejected_move: self.rejected_move,
+        }
+    }
+
+    /// `slot` failed to move; the game goes to the other player.
+    fn forfeit(self, slot: usize, reason: &str) -> RefereeResult {
+        self.finish(Some(1 - slot), reason)
+    }
+}
+
+/// Start both agents and send them their first message. Fails with the slot
+/// of the agent that could not be started.
+fn start_agents(commands: [&str; 2], messages: [String; 2]) -> Result<[Agent; 2], (usize, String)> {
+    let mut agents = Vec::new();
+    for (slot, command) in commands.iter().enumerate() {
+        agents.push(Agent::spawn(command).map_err(|error| (slot, error))?);
+    }
+
+    let mut agents: [Agent; 2] = agents.try_into().unwrap_or_else(|_| unreachable!());
+    for (agent, message) in agents.iter_mut().zip(messages) {
+        agent.write(&message);
+    }
+    Ok(agents)
+}
+
+/// Forfeit reason for an agent that answered with `line` (or failed to answer).
+/// `well_formed` says whether the answer had the shape of a move.
+fn forfeit_reason(agent: &mut Agent, line: &Result<String, ReadError>, well_formed: bool) -> &'static str {
+    match line {
+        Err(ReadError::Timeout) => "timeout",
+        Err(ReadError::Closed) => "crash",
+        Ok(line) if line.is_empty() && agent.has_exited() => "crash",
+        Ok(_) if !well_formed => "protocol_violation",
+        Ok(_) => "invalid_move",
+    }
+}
+
+/// Stop both agents once the game is over.
+fn stop_agents(agents: &mut [Agent; 2]) {
+    // Close both inputs first so the agents shut down side by side
+    for agent in agents.iter_mut() {
+        agent.close_input();
+    }
+    for agent in agents.iter_mut() {
+        agent.stop();
+    }
+}
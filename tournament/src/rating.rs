@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{glicko2, trueskill, MatchResult};
+
+/// Within this many deviations either side of the rating the true strength
+/// lies with 95% confidence.
+const Z_95: f64 = 1.96;
+
+/// How ratings are computed.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingModel {
+    /// Plain Elo, without an uncertainty estimate.
+    #[default]
+    Elo,
+    /// Glicko-2: a rating, a rating deviation and a volatility per agent,
+    /// with every round of the tournament as one rating period.
+    Glicko2,
+    /// A TrueSkill-style Bayesian model (a mean and a standard deviation per
+    /// agent), updated game by game.
+    Trueskill,
+}
+
+/// The `[ratings]` section of the tournament configuration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct RatingConfig {
+    #[serde(default)]
+    pub model: RatingModel,
+    /// Most rating points that change hands in one game (Elo only).
+    pub k_factor: Option<f64>,
+    /// Rating every agent starts from.
+    pub initial_rating: Option<f64>,
+    /// Uncertainty every agent starts with (Glicko-2 and TrueSkill).
+    pub initial_deviation: Option<f64>,
+    /// How much volatility may change between rating periods (Glicko-2 only).
+    pub tau: Option<f64>,
+}
+
+impl RatingConfig {
+    pub fn k_factor(&self) -> f64 {
+        self.k_factor.unwrap_or(32.0)
+    }
+
+    pub fn initial_rating(&self) -> f64 {
+        match (self.initial_rating, self.model) {
+            (Some(rating), _) => rating,
+            (None, RatingModel::Trueskill) => 25.0,
+            (None, _) => 1500.0,
+        }
+    }
+
+    pub fn initial_deviation(&self) -> f64 {
+        match (self.initial_deviation, self.model) {
+            (Some(deviation), _) => deviation,
+            (None, RatingModel::Trueskill) => self.initial_rating() / 3.0,
+            (None, _) => 350.0,
+        }
+    }
+
+    pub fn tau(&self) -> f64 {
+        self.tau.unwrap_or(0.5)
+    }
+
+    /// Title of the rating table.
+    pub fn describe(&self) -> String {
+        match self.model {
+            RatingModel::Elo => format!("Elo, K = {}", self.k_factor()),
+            RatingModel::Glicko2 => "Glicko-2".to_string(),
+            RatingModel::Trueskill => "TrueSkill".to_string(),
+        }
+    }
+}
+
+/// One line of the rating table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    pub rank: usize,
+    pub player: String,
+    pub rating: f64,
+    /// Standard deviation of the rating, for models that estimate one.
+    pub deviation: Option<f64>,
+    pub games: u32,
+}
+
+impl Rating {
+    /// Range the true rating lies in with 95% confidence.
+    pub fn interval(&self) -> Option<(f64, f64)> {
+        self.deviation.map(|deviation| (self.rating - Z_95 * deviation, self.rating + Z_95 * deviation))
+    }
+
+    /// Whether this rating is ahead of `next` beyond doubt: the two 95%
+    /// intervals do not overlap.
+    pub fn clear_of(&self, next: &Rating) -> Option<bool> {
+        Some(self.interval()?.0 > next.interval()?.1)
+    }
+}
+
+/// Ratings of `players` (and anyone else in `games`) from `games` in the
+/// order given, best first. Games without a result are skipped; a forfeit is
+/// rated as a loss.
+pub fn rate(players: &[String], games: &[MatchResult], config: &RatingConfig) -> Vec<Rating> {
+    let games: Vec<&MatchResult> = games.iter().filter(|game| !game.is_no_contest()).collect();
+    let mut table = match config.model {
+        RatingModel::Elo => elo(players, &games, config.k_factor(), config.initial_rating()),
+        RatingModel::Glicko2 => glicko2::rate(players, &games, config.initial_rating(), config.initial_deviation(), config.tau()),
+        RatingModel::Trueskill => trueskill::rate(players, &games, config.initial_rating(), config.initial_deviation()),
+    };
+
+    table.sort_by(|a, b| b.rating.total_cmp(&a.rating).then_with(|| a.player.cmp(&b.player)));
+    for (index, rating) in table.iter_mut().enumerate() {
+        rating.rank = index + 1;
+    }
+    table
+}
+
+/// Score of player 1 in a game with a result: 1 for a win, 0.5 for a draw.
+pub fn first_player_score(game: &MatchResult) -> f64 {
+    if game.is_draw {
+        0.5
+    } else if game.winner.as_deref() == Some(game.player1.as_str()) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Every player in `players` or `games`, each with `start`.
+pub fn starting_table<T: Copy>(players: &[String], games: &[&MatchResult], start: T) -> BTreeMap<String, (T, u32)> {
+    players.iter()
+        .chain(games.iter().flat_map(|game| [&game.player1, &game.player2]))
+        .map(|player| (player.clone(), (start, 0)))
+        .collect()
+}
+
+fn elo(players: &[String], games: &[&MatchResult], k_factor: f64, initial_rating: f64) -> Vec<Rating> {
+    let mut ratings = starting_table(players, games, initial_rating);
+
+    for game in games {
+        let (rating1, rating2) = (ratings[&game.player1].0, ratings[&game.player2].0);
+        let expected = 1.0 / (1.0 + 10f64.powf((rating2 - rating1) / 400.0));
+        let change = k_factor * (first_player_score(game) - expected);
+
+        for (player, change) in [(&game.player1, change), (&game.player2, -change)] {
+            if let Some(entry) = ratings.get_mut(player) {
+                entry.0 += change;
+                entry.1 += 1;
+            }
+        }
+    }
+
+    ratings.into_iter()
+        .map(|(player, (rating, games))| Rating { rank: 0, player, rating, deviation: None, games })
+        .collect()
+}
+
+/// One row of the ratings CSV.
+#[derive(Serialize)]
+struct Row<'a> {
+    #[serde(rename = "Rank")]
+    rank: usize,
+    #[serde(rename = "Player")]
+    player: &'a str,
+    #[serde(rename = "Rating")]
+    rating: f64,
+    #[serde(rename = "Deviation")]
+    deviation: Option<f64>,
+    #[serde(rename = "Low")]
+    low: Option<f64>,
+    #[serde(rename = "High")]
+    high: Option<f64>,
+    #[serde(rename = "Games")]
+    games: u32,
+}
+
+/// Write `ratings` to a CSV file, one row per player.
+pub fn export(path: &str, ratings: &[Rating]) -> Result<(), Box<dyn std::error::Error>> {
+    let round = |value: f64| (value * 10.0).round() / 10.0;
+    let mut writer = csv::Writer::from_path(path)?;
+    for rating in ratings {
+        let interval = rating.interval();
+        writer.serialize(Row {
+            rank: rating.rank,
+            player: &rating.player,
+            rating: round(rating.rating),
+            deviation: rating.deviation.map(round),
+            low: interval.map(|(low, _)| round(low)),
+            high: interval.map(|(_, high)| round(high)),
+            games: rating.games,
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn elo_updates_game_by_game() {
+        let games = [MatchResult::test("a", "b", Some("a")), MatchResult::test("b", "c", None), MatchResult { is_draw: false, ..MatchResult::test("c", "a", None) }];
+        let ratings = rate(&players(&["a", "b", "c"]), &games, &RatingConfig::default());
+
+        // Even players swap 16 points; the draw then moves 32 * (0.5 - expected) towards b
+        let expected_draw = 32.0 * (0.5 - 1.0 / (1.0 + 10f64.powf(16.0 / 400.0)));
+        assert_eq!(ratings.iter().map(|rating| rating.player.as_str()).collect::<Vec<_>>(), ["a", "c", "b"]);
+        assert_eq!(ratings[0].rating, 1516.0);
+        assert!((ratings[1].rating - (1500.0 - expected_draw)).abs() < 1e-9);
+        assert!((ratings[2].rating - (1484.0 + expected_draw)).abs() < 1e-9);
+        assert_eq!(ratings.iter().map(|rating| rating.games).collect::<Vec<_>>(), [1, 1, 2]);
+        assert_eq!(ratings[2].rank, 3);
+    }
+
+    #[test]
+    fn k_factor_and_initial_rating_are_used() {
+        let config = RatingConfig { k_factor: Some(10.0), initial_rating: Some(1000.0), ..RatingConfig::default() };
+        let ratings = rate(&players(&["a", "b", "idle"]), &[MatchResult::test("b", "a", Some("a"))], &config);
+        let table: Vec<(&str, f64)> = ratings.iter().map(|rating| (rating.player.as_str(), rating.rating)).collect();
+        assert_eq!(table, [("a", 1005.0), ("idle", 1000.0), ("b", 995.0)]);
+    }
+}
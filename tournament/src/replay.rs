@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use crate::game;
+use crate::transcript::Transcript;
+
+/// Print the board after every move of a saved game, or only after move
+/// `jump_to` (0 is the board before the first move).
+pub fn run(path: &str, jump_to: Option<usize>) -> Result<(), String> {
+    let transcript = Transcript::load(path)?;
+    print!("{}", render(&transcript, jump_to)?);
+    Ok(())
+}
+
+fn render(transcript: &Transcript, jump_to: Option<usize>) -> Result<String, String> {
+    let game = game::find(&transcript.game)
+        .ok_or_else(|| format!("The transcript is of an unknown game: {}", transcript.game))?;
+    let result = &transcript.result;
+    let moves = &transcript.history.moves;
+    if let Some(number) = jump_to.filter(|&number| number > moves.len()) {
+        return Err(format!("Cannot jump to move {}: the game has {} moves", number, moves.len()));
+    }
+
+    let [color1, color2] = game.colors();
+    let player = |slot: &str| match slot {
+        "player1" => format!("{} ({})", result.player1, result.player1_color.as_deref().unwrap_or(color1)),
+        _ => format!("{} ({})", result.player2, result.player2_color.as_deref().unwrap_or(color2)),
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{}, {} game {}: {} vs {}",
+                     result.round, result.group, result.game_number, player("player1"), player("player2"));
+
+    let mut state = game.new_state();
+    if jump_to.is_none_or(|number| number == 0) {
+        let _ = writeln!(out, "\nStart\n{}", state.board());
+    }
+    for (index, record) in moves.iter().enumerate() {
+        let number = index + 1;
+        let outcome = match state.play(&record.text) {
+            Ok(outcome) => outcome,
+            Err(illegal) => {
+                let _ = writeln!(out, "\nMove {} ({}) is illegal under the runner's rules: {}", number, record.text, illegal.reason);
+                return Ok(out);
+            }
+        };
+        if jump_to.is_none_or(|jump| jump == number) {
+            let time = record.time.map(|time| format!(" ({:.3}s)", time)).unwrap_or_default();
+            let _ = writeln!(out, "\nMove {}: {} plays {}{}\n{}", number, player(&record.player), record.text, time, state.board());
+        }
+        if jump_to == Some(number) {
+            break;
+        }
+        // The rules ended the game here, so any later moves cannot be shown
+        if let Some((_, termination)) = outcome.filter(|_| number < moves.len()) {
+            let _ = writeln!(out, "\nThe game ends after move {} under the runner's rules ({}), but the transcript has {} more move(s)",
+                             number, termination, moves.len() - number);
+            return Ok(out);
+        }
+    }
+
+    // The outcome belongs after the last move
+    if jump_to.is_none_or(|number| number == moves.len()) {
+        if let Some(record) = &transcript.history.rejected_move {
+            let _ = writeln!(out, "\n{} then sent '{}', which was rejected", player(&record.player), record.text);
+        }
+        let termination = result.termination.as_deref().unwrap_or("unknown");
+        let _ = match (&result.winner, result.is_draw) {
+            (Some(winner), _) => writeln!(out, "\nResult: {} wins ({})", winner, termination),
+            (None, true) => writeln!(out, "\nResult: draw ({})", termination),
+            (None, false) => writeln!(out, "\nResult: no result ({})", result.error.as_deref().unwrap_or(termination)),
+        };
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::MoveRecord;
+
+    fn transcript() -> Transcript {
+        serde_json::from_str(r#"{
+            "version": 1, "game": "tictactoe",
+            "result": {"round": "Final", "group": "Group A", "game_number": 2, "player1": "alice", "player2": "bob",
+                       "winner": "alice", "is_draw": false, "termination": "invalid_move"},
+            "moves": [{"player": "player1", "move": "b2", "time": 0.25}, {"player": "player2", "move": "a1", "time": null}],
+            "rejected_move": {"player": "player2", "move": "b2", "time": 0.5},
+            "board": null
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn replays_every_move() {
+        let out = render(&transcript(), None).unwrap();
+        assert!(out.starts_with("Final, Group A game 2: alice (blue) vs bob (orange)\n\nStart\n3 . . .\n"), "{}", out);
+        assert!(out.contains("\nMove 1: alice (blue) plays b2 (0.250s)\n3 . . .\n2 . X .\n1 . . .\n  a b c\n"), "{}", out);
+        assert!(out.contains("\nMove 2: bob (orange) plays a1\n"), "{}", out);
+        assert!(out.ends_with("\nbob (orange) then sent 'b2', which was rejected\n\nResult: alice wins (invalid_move)\n"), "{}", out);
+    }
+
+    #[test]
+    fn stops_where_the_rules_end_the_game() {
+        let mut transcript = transcript();
+        transcript.history.moves = ["a1", "b1", "a2", "b2", "a3", "c3"].iter().enumerate()
+            .map(|(index, text)| MoveRecord {
+                player: format!("player{}", index % 2 + 1),
+                text: text.to_string(),
+                time: None,
+            })
+            .collect();
+        let out = render(&transcript, None).unwrap();
+        assert!(out.contains("\nMove 5: alice (blue) plays a3\n"), "{}", out);
+        assert!(out.ends_with("\nThe game ends after move 5 under the runner's rules (three_in_a_row), \
+                               but the transcript has 1 more move(s)\n"), "{}", out);
+    }
+
+    #[test]
+    fn jumps_to_a_move() {
+        let out = render(&transcript(), Some(1)).unwrap();
+        assert!(out.contains("Move 1:") && !out.contains("Start") && !out.contains("Move 2:") && !out.contains("Result"), "{}", out);
+        assert!(render(&transcript(), Some(2)).unwrap().contains("Result: alice wins"));
+        assert!(render(&transcript(), Some(3)).is_err());
+    }
+}
@@ -0,0 +1,615 @@
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use crate::agent;
+use crate::game::{self, Game};
+use crate::native;
+use crate::transcript::{self, History, Transcript};
+use crate::{Engine, Forfeit, GameSettings, MatchResult};
+
+/// Hands out web visualization ports so games running at the same time never
+/// share one.
+#[derive(Debug)]
+pub struct PortPool {
+    range: RangeInclusive<u16>,
+    in_use: Mutex<HashSet<u16>>,
+    released: Condvar,
+}
+
+/// A port reserved for one game; it goes back to the pool when dropped.
+pub struct PortLease<'a> {
+    pool: &'a PortPool,
+    pub port: u16,
+}
+
+impl PortPool {
+    pub fn new(range: RangeInclusive<u16>) -> Self {
+        PortPool { range, in_use: Mutex::new(HashSet::new()), released: Condvar::new() }
+    }
+
+    /// Reserve the lowest port in the range that is neither handed out nor
+    /// taken by another program on this machine. If every free port is held
+    /// by another game, wait for one of them to finish.
+    pub fn acquire(&self) -> Result<PortLease<'_>, String> {
+        let mut in_use = self.in_use.lock().unwrap();
+        loop {
+            let free = self.range.clone()
+                .find(|port| !in_use.contains(port) && TcpListener::bind(("0.0.0.0", *port)).is_ok());
+            if let Some(port) = free {
+                in_use.insert(port);
+                return Ok(PortLease { pool: self, port });
+            }
+            if in_use.is_empty() {
+                return Err(format!(
+                    "No free port between {} and {} for the game visualization; set settings.port_range to an unused range",
+                    self.range.start(), self.range.end()
+                ));
+            }
+            in_use = self.released.wait(in_use).unwrap();
+        }
+    }
+}
+
+impl Drop for PortLease<'_> {
+    fn drop(&mut self) {
+        self.pool.in_use.lock().unwrap().remove(&self.port);
+        self.pool.released.notify_one();
+    }
+}
+
+/// The `RESULT {...}` line printed by referees started with `--json-result`.
+/// The native engines report in the same shape.
+#[derive(Debug, Deserialize)]
+pub struct RefereeResult {
+    /// "player1" or "player2", following the order of the command line.
+    pub winner: Option<String>,
+    pub termination: String,
+    pub moves: Option<u32>,
+    /// Color of each player slot, e.g. {"player1": "blue", "player2": "orange"}.
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+    #[serde(default)]
+    pub timings: BTreeMap<String, MoveTimings>,
+    pub error: Option<String>,
+    /// Every legal move in order.
+    #[serde(default)]
+    pub history: Vec<MoveRecord>,
+    /// The move that lost the game by forfeit, if the player sent one.
+    pub rejected_move: Option<MoveRecord>,
+}
+
+/// One move of a game and the seconds the player took for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    /// "player1" or "player2".
+    pub player: String,
+    #[serde(rename = "move")]
+    pub text: String,
+    pub time: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveTimings {
+    /// Seconds spent waiting for the player's moves, in total.
+    pub total: f64,
+}
+
+/// Plays single games for the tournament. `MatchRunner` is the real one; tests
+/// substitute their own to control results and timing.
+pub trait PlayGame: std::fmt::Debug + Send + Sync {
+    fn run_match(
+        &self,
+        round: &str,
+        group: &str,
+        game_number: i32,
+        player1: &str,
+        player2: &str,
+    ) -> Result<MatchResult, String>;
+}
+
+/// Everything needed to run a single game. Owns copies of the relevant config
+/// so it can be shared read-only between worker threads.
+#[derive(Debug)]
+pub struct MatchRunner {
+    pub game: &'static dyn Game,
+    pub agents: BTreeMap<String, String>,
+    pub settings: GameSettings,
+    pub ports: PortPool,
+    /// Directory game transcripts are saved in, if any.
+    pub transcripts: Option<PathBuf>,
+    /// Set once the referee turns out to predate `--json-result`.
+    legacy_referee: AtomicBool,
+}
+
+impl MatchRunner {
+    pub fn new(game: &'static dyn Game, agents: &BTreeMap<String, String>, settings: GameSettings) -> Self {
+        MatchRunner {
+            game,
+            agents: agents.clone(),
+            ports: PortPool::new(settings.port_range()),
+            settings,
+            transcripts: None,
+            legacy_referee: AtomicBool::new(false),
+        }
+    }
+}
+
+impl PlayGame for MatchRunner {
+    fn run_match(
+        &self,
+        round: &str,
+        group: &str,
+        game_number: i32,
+        player1: &str,
+        player2: &str,
+    ) -> Result<MatchResult, String> {
+        let p1_cmd = self.agents.get(player1).ok_or_else(|| format!("Unknown player: {}", player1))?;
+        let p2_cmd = self.agents.get(player2).ok_or_else(|| format!("Unknown player: {}", player2))?;
+
+        // Set up the command arguments based on the game type
+        let settings = &self.settings;
+
+        let mut cmd_args = Vec::new();
+
+        if settings.engine == Some(Engine::Native) {
+            let timeout = Duration::from_secs(settings.timeout.unwrap_or(5).max(1) as u64);
+            let report = native::play(self.game, [p1_cmd.as_str(), p2_cmd.as_str()], timeout);
+            let (result, history) = self.read_report(round, report, player1, player2);
+            return Ok(self.finish_result(result, history, group, game_number));
+        }
+
+        cmd_args.push(self.game.name().to_string());
+        cmd_args.push(self.game.player1_flag().to_string());
+        cmd_args.push(p1_cmd.clone());
+        cmd_args.push("--player2".to_string());
+        cmd_args.push(p2_cmd.clone());
+
+        // Add optional settings
+        if let Some(timeout) = settings.timeout {
+            cmd_args.push("--timeout".to_string());
+            cmd_args.push(timeout.to_string());
+        }
+
+        // Visual games each get their own port, held until the referee exits
+        let visual = settings.visual.unwrap_or(false); // Default to no visual
+        let lease = if visual { Some(self.ports.acquire()?) } else { None };
+        if visual {
+            cmd_args.push("--visual".to_string());
+        } else {
+            cmd_args.push("--no-visual".to_string());
+        }
+
+        // Colors are already fixed by the tournament schedule, so the
+        // referee must keep player 1 as X / blue
+        cmd_args.push("--no-random-assignment".to_string());
+
+        if let Some(debug) = settings.debug {
+            if debug {
+                cmd_args.push("--debug".to_string());
+            } else {
+                cmd_args.push("--no-debug".to_string());
+            }
+        }
+
+        if let Some(lease) = &lease {
+            let url = format!("http://localhost:{}", lease.port);
+            info!("Watch {} vs {} ({} game {}) at {}", player1, player2, group, game_number, url);
+            println!("Watch {} vs {} ({} game {}) at {}", player1, player2, group, game_number, url);
+            cmd_args.push("--port".to_string());
+            cmd_args.push(lease.port.to_string());
+        }
+
+        // Ask for the structured result unless the referee is known not to support it
+        let mut json_result = !self.legacy_referee.load(Ordering::Relaxed);
+        let (stdout, stderr) = loop {
+            let mut args = cmd_args.clone();
+            if json_result {
+                args.push("--json-result".to_string());
+            }
+            let (stdout, stderr) = Self::run_referee(&args)?;
+
+            if json_result && stderr.contains("No such option") && stderr.contains("--json-result") {
+                if !self.legacy_referee.swap(true, Ordering::Relaxed) {
+                    warn!("The referee does not support --json-result; reading results from its text output instead. \
+                           Update the referee for reliable results.");
+                }
+                json_result = false;
+                continue;
+            }
+            break (stdout, stderr);
+        };
+
+        // Parse result to determine winner
+        let (result, history) = match parse_json_result(&stdout) {
+            Some(report) => self.read_report(round, report, player1, player2),
+            None => {
+                if json_result {
+                    warn!("No RESULT line in the referee output; reading its text output instead");
+                }
+                (self.parse_game_result(round, &stdout, &stderr, player1, player2), History::default())
+            }
+        };
+
+        Ok(self.finish_result(result, history, group, game_number))
+    }
+}
+
+impl MatchRunner {
+    /// Set the group and game number in the result, save its transcript and log it.
+    fn finish_result(&self, mut result: MatchResult, history: History, group: &str, game_number: i32) -> MatchResult {
+        result.group = group.to_string();
+        result.game_number = game_number;
+
+        if let Some(dir) = &self.transcripts {
+            let path = transcript::path(dir, &result.round, group, game_number);
+            result.transcript = Some(path.display().to_string());
+            if let Err(e) = Transcript::new(self.game, &result, history).save(&path) {
+                warn!("Could not save the transcript of {} game {}: {}", group, game_number, e);
+                result.transcript = None;
+            }
+        }
+
+        info!("Match result: {} vs {} - Winner: {:?}, Draw: {}, Forfeit: {:?}",
+              result.player1, result.player2, result.winner, result.is_draw, result.forfeit);
+        if result.is_no_contest() {
+            warn!("{} vs {} ({} game {}) has no result and counts for neither player",
+                  result.player1, result.player2, group, game_number);
+        }
+
+        result
+    }
+
+    fn run_referee(args: &[String]) -> Result<(String, String), String> {
+        // Log the command being executed
+        let cmd_str = format!("uv run cs4341-referee {}", args.join(" "));
+        debug!("Executing command: {}", cmd_str);
+
+        // Run the command
+        let output = Command::new("uv")
+            .arg("run")
+            .arg("cs4341-referee")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|e| format!("Failed to start the referee: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        // Log the output
+        debug!("Command stdout: {}", stdout);
+        if !stderr.is_empty() {
+            warn!("Command stderr: {}", stderr);
+        }
+
+        Ok((stdout, stderr))
+    }
+
+    fn empty_result(round: &str, player1: &str, player2: &str) -> MatchResult {
+        MatchResult {
+            round: round.to_string(),
+            group: String::new(), // Will be filled later
+            game_number: 0,       // Will be filled later
+            player1: player1.to_string(),
+            player2: player2.to_string(),
+            player1_color: None,
+            player2_color: None,
+            winner: None,
+            is_draw: false,
+            forfeit: None,
+            termination: None,
+            moves: None,
+            player1_time: None,
+            player2_time: None,
+            error: None,
+            transcript: None,
+        }
+    }
+
+    /// Turn a referee (or native engine) report into a match result and the
+    /// moves for its transcript.
+    fn read_report(&self, round: &str, parsed: RefereeResult, player1: &str, player2: &str) -> (MatchResult, History) {
+        let mut result = Self::empty_result(round, player1, player2);
+        result.winner = match parsed.winner.as_deref() {
+            Some("player1") => Some(player1.to_string()),
+            Some("player2") => Some(player2.to_string()),
+            _ => None,
+        };
+        result.forfeit = Forfeit::parse(&parsed.termination);
+        result.moves = parsed.moves;
+        result.player1_color = parsed.colors.get("player1").cloned();
+        result.player2_color = parsed.colors.get("player2").cloned();
+        if result.player1_color.as_deref().is_some_and(|color| color != self.game.colors()[0]) {
+            warn!("The referee gave {} the {} pieces although colors were assigned by the runner",
+                  player1, result.player1_color.as_deref().unwrap_or_default());
+        }
+        result.player1_time = parsed.timings.get("player1").map(|timings| timings.total);
+        result.player2_time = parsed.timings.get("player2").map(|timings| timings.total);
+
+        if parsed.termination == "error" {
+            let error = parsed.error.unwrap_or_else(|| "The referee reported an error".to_string());
+            // An agent that cannot even be started forfeits the game
+            if let Some((loser, winner)) = self.crashed_player(&error, player1, player2) {
+                warn!("{} forfeits against {} (crash)", loser, winner);
+                result.winner = Some(winner.to_string());
+                result.forfeit = Some(Forfeit::Crash);
+            }
+            result.error = Some(error);
+        } else {
+            if result.winner.is_none() {
+                result.is_draw = true;
+            } else if let Some(reason) = result.forfeit {
+                let loser = if result.winner.as_deref() == Some(player1) { player2 } else { player1 };
+                warn!("{} forfeits ({})", loser, reason.describe());
+            }
+            result.error = parsed.error;
+        }
+        result.termination = Some(parsed.termination);
+
+        (result, History { moves: parsed.history, rejected_move: parsed.rejected_move })
+    }
+
+    /// `(player, opponent)` for the player the referee calls `color`. Player 1
+    /// always has the first color, since the referee is started with
+    /// `--no-random-assignment`; the JSON result confirms this per game.
+    fn players_for_color<'a>(&self, color: &str, player1: &'a str, player2: &'a str) -> Option<(&'a str, &'a str)> {
+        match game::slot_for_color(self.game, color)? {
+            0 => Some((player1, player2)),
+            _ => Some((player2, player1)),
+        }
+    }
+
+    /// The player whose command is named in a referee error, if exactly one
+    /// is. Only words of the command the other agent's lacks count, so a
+    /// shared interpreter (`python3 a.py` against `python3 b.py`) is
+    /// attributed by the script instead.
+    fn crashed_player<'a>(&self, error: &str, player1: &'a str, player2: &'a str) -> Option<(&'a str, &'a str)> {
+        let words = |player: &str| {
+            self.agents.get(player)
+                .and_then(|command| agent::split_command(command).ok())
+                .unwrap_or_default()
+        };
+        let (words1, words2) = (words(player1), words(player2));
+        // Python quotes the path it could not open, sometimes made absolute
+        let quoted = |word: &String| {
+            error.contains(&format!("'{}'", word)) || error.contains(&format!("/{}'", word.trim_start_matches("./")))
+        };
+        let named = |own: &[String], other: &[String]| own.iter().filter(|word| !other.contains(word)).any(quoted);
+        match (named(&words1, &words2), named(&words2, &words1)) {
+            (true, false) => Some((player1, player2)),
+            (false, true) => Some((player2, player1)),
+            _ => None,
+        }
+    }
+
+    fn parse_game_result(
+        &self,
+        round: &str,
+        stdout: &str,
+        stderr: &str,
+        player1: &str,
+        player2: &str,
+    ) -> MatchResult {
+        let mut result = Self::empty_result(round, player1, player2);
+        // Old referees do not report colors; with --no-random-assignment player 1 has the first
+        let [color1, color2] = self.game.colors();
+        result.player1_color = Some(color1.to_string());
+        result.player2_color = Some(color2.to_string());
+        let stdout = &strip_ansi(stdout);
+
+        // The referee names the side that failed to move and why
+        let forfeit = stdout.lines()
+            .find_map(|line| line.trim().strip_prefix("Forfeit: "))
+            .and_then(|rest| {
+                let (color, reason) = rest.split_once(' ')?;
+                let reason = reason.trim_start_matches('(').trim_end_matches(')');
+                Some((color, Forfeit::parse(reason)?))
+            })
+            // Older referees only report timeouts: "Move timeout: Player BLUE ..." / "Move timeout: blue"
+            .or_else(|| {
+                let rest = stdout.lines().find_map(|line| line.trim().strip_prefix("Move timeout: "))?;
+                let color = rest.trim_start_matches("Player ").split_whitespace().next()?;
+                Some((color, Forfeit::Timeout))
+            });
+        if let Some((color, reason)) = forfeit {
+            if let Some((loser, winner)) = self.players_for_color(color, player1, player2) {
+                warn!("{} forfeits against {} ({})", loser, winner, reason.describe());
+                result.winner = Some(winner.to_string());
+                result.forfeit = Some(reason);
+                return result;
+            }
+        }
+
+        // Check for errors; the referee reports its own failures on stdout
+        let referee_error = stdout.lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("Error:"))
+            .map(str::to_string);
+        let stderr_error = (!stderr.is_empty() && (stderr.contains("Error") || stderr.contains("error")))
+            .then(|| stderr.to_string());
+        if let Some(error) = referee_error.or(stderr_error) {
+            // An agent that cannot even be started forfeits the game
+            if let Some((loser, winner)) = self.crashed_player(&error, player1, player2) {
+                warn!("{} forfeits against {} (crash)", loser, winner);
+                result.winner = Some(winner.to_string());
+                result.forfeit = Some(Forfeit::Crash);
+            }
+            result.error = Some(error);
+            return result;
+        }
+
+        // Look for game over message
+        if stdout.contains("Game over! Draw!") || stdout.contains("Game over! It's a draw!") {
+            result.is_draw = true;
+            return result;
+        }
+
+        // Check for winner: "BLUE" / "ORANGE" (tictactoe), "blue" / "orange"
+        // (Lasker Morris) or "Player X" / "Player O" (older referees)
+        let winner_color = stdout.lines()
+            .find_map(|line| line.trim().strip_prefix("Game over! Winner: "))
+            .map(|rest| rest.trim_start_matches("Player ").trim());
+        if let Some((winner, _)) = winner_color.and_then(|color| self.players_for_color(color, player1, player2)) {
+            result.winner = Some(winner.to_string());
+        } else if let Some(color) = winner_color {
+            warn!("Unrecognized winner '{}' in the referee output", color);
+        }
+
+        result
+    }
+}
+
+/// Read the `RESULT {json}` line of a referee started with `--json-result`.
+fn parse_json_result(stdout: &str) -> Option<RefereeResult> {
+    let json = stdout.lines().find_map(|line| strip_ansi(line).trim().strip_prefix("RESULT ").map(str::to_string))?;
+    match serde_json::from_str(&json) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warn!("Could not read the referee result {}: {}", json, e);
+            None
+        }
+    }
+}
+
+/// Drop terminal color codes (`ESC [ ... m`) from referee output.
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip up to and including the final letter of the sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner() -> MatchRunner {
+        let agents = [("alice", "python3 alice.py"), ("bob", "./bob")].iter()
+            .map(|(name, command)| (name.to_string(), command.to_string()))
+            .collect();
+        MatchRunner::new(game::find("laskermorris").unwrap(), &agents, GameSettings::default())
+    }
+
+    fn read_json(stdout: &str) -> Option<MatchResult> {
+        parse_json_result(stdout).map(|report| runner().read_report("R", report, "alice", "bob").0)
+    }
+
+    #[test]
+    fn json_result_names_the_winner_by_slot() {
+        let stdout = "Game over! Winner: orange\nRESULT {\"version\": 1, \"winner\": \"player2\", \"termination\": \"timeout\", \
+                      \"moves\": 17, \"timings\": {\"player1\": {\"total\": 5.5, \"max\": 5.5, \"moves\": 9}, \
+                      \"player2\": {\"total\": 1.25, \"max\": 0.5, \"moves\": 8}}, \
+                      \"history\": [{\"player\": \"player1\", \"move\": \"a1\", \"time\": 0.5}], \
+                      \"rejected_move\": {\"player\": \"player2\", \"move\": \"zz\", \"time\": null}}\n";
+        let (result, history) = runner().read_report("R", parse_json_result(stdout).unwrap(), "alice", "bob");
+        assert_eq!(result.winner.as_deref(), Some("bob"));
+        assert_eq!(result.forfeit, Some(Forfeit::Timeout));
+        assert_eq!(result.moves, Some(17));
+        assert_eq!((result.player1_time, result.player2_time), (Some(5.5), Some(1.25)));
+        assert_eq!(history.moves[0].text, "a1");
+        assert_eq!(history.rejected_move.map(|record| record.text).as_deref(), Some("zz"));
+
+        let swapped = "RESULT {\"version\": 1, \"winner\": \"player1\", \"termination\": \"three_in_a_row\", \
+                       \"colors\": {\"player1\": \"orange\", \"player2\": \"blue\"}}";
+        let result = read_json(swapped).unwrap();
+        assert_eq!(result.winner.as_deref(), Some("alice"));
+        assert_eq!(result.player1_color.as_deref(), Some("orange"));
+
+        let draw = "RESULT {\"version\": 1, \"winner\": null, \"termination\": \"board_full\", \"moves\": 9}";
+        assert!(read_json(draw).unwrap().is_draw);
+        assert!(read_json("Game over! Draw!").is_none());
+    }
+
+    #[test]
+    fn forfeit_goes_to_the_opponent() {
+        let stdout = "\u{1b}[31mInvalid target position: z9\u{1b}[0m\nForfeit: blue (invalid_move)\n\nGame over! Winner: orange\n";
+        let result = runner().parse_game_result("R", stdout, "", "alice", "bob");
+        assert_eq!(result.winner.as_deref(), Some("bob"));
+        assert_eq!(result.forfeit, Some(Forfeit::InvalidMove));
+
+        let legacy = "Move timeout: Player ORANGE took too long to respond\nGame over! Winner: BLUE\n";
+        let result = runner().parse_game_result("R", legacy, "", "alice", "bob");
+        assert_eq!(result.winner.as_deref(), Some("alice"));
+        assert_eq!(result.forfeit, Some(Forfeit::Timeout));
+    }
+
+    #[test]
+    fn text_winner_is_mapped_by_color() {
+        let mut tictactoe = runner();
+        tictactoe.game = game::find("tictactoe").unwrap();
+        for (runner, stdout, winner) in [
+            (&tictactoe, "\u{1b}[34m\nGame over! Winner: BLUE\u{1b}[0m\n", "alice"),
+            (&runner(), "Game over! Winner: orange\n", "bob"),
+            (&tictactoe, "Game over! Winner: Player O\n", "bob"),
+        ] {
+            let result = runner.parse_game_result("R", stdout, "", "alice", "bob");
+            assert_eq!(result.winner.as_deref(), Some(winner), "{}", stdout);
+        }
+    }
+
+    #[test]
+    fn agent_that_cannot_start_forfeits() {
+        let stdout = "\nError: [Errno 2] No such file or directory: './bob'\n";
+        let result = runner().parse_game_result("R", stdout, "Aborted!\n", "alice", "bob");
+        assert_eq!(result.winner.as_deref(), Some("alice"));
+        assert_eq!(result.forfeit, Some(Forfeit::Crash));
+        assert!(result.error.is_some());
+
+        // Nobody to blame: no result for either player
+        let result = runner().parse_game_result("R", "Error: referee exploded\n", "", "alice", "bob");
+        assert!(result.is_no_contest());
+    }
+
+    #[test]
+    fn crash_is_attributed_when_agents_share_an_interpreter() {
+        let agents = [("alice", "python3 alice.py"), ("bob", "python3 'bots/my bob.py'")].iter()
+            .map(|(name, command)| (name.to_string(), command.to_string()))
+            .collect();
+        let runner = MatchRunner::new(game::find("tictactoe").unwrap(), &agents, GameSettings::default());
+
+        let crashed = |error: &str| runner.crashed_player(error, "alice", "bob");
+        assert_eq!(crashed("[Errno 2] No such file or directory: 'bots/my bob.py'"), Some(("bob", "alice")));
+        assert_eq!(crashed("python3: can't open file '/home/t/alice.py': [Errno 2]"), Some(("alice", "bob")));
+        // The interpreter itself is missing: it could be either agent
+        assert_eq!(crashed("[Errno 2] No such file or directory: 'python3'"), None);
+    }
+
+    #[test]
+    fn leases_are_unique_until_released() {
+        let pool = PortPool::new(47310..=47319);
+        let first = pool.acquire().unwrap();
+        let second = pool.acquire().unwrap();
+        assert_ne!(first.port, second.port);
+
+        let port = first.port;
+        drop(first);
+        assert_eq!(pool.acquire().unwrap().port, port);
+    }
+
+    #[test]
+    fn blocked_range_is_an_error() {
+        let _other_program = TcpListener::bind(("0.0.0.0", 47320)).unwrap();
+        let pool = PortPool::new(47320..=47320);
+        assert!(pool.acquire().is_err());
+    }
+}
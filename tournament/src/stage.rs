@@ -0,0 +1,397 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// One entry of the `[[stages]]` pipeline in the tournament configuration.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StageConfig {
+    pub name: String,
+    #[serde(default)]
+    pub format: StageFormat,
+    /// Number of groups to split the entrants into (round robin only).
+    #[serde(default = "default_groups")]
+    pub groups: usize,
+    /// How many players from each group move on to the next stage.
+    pub advance: Option<usize>,
+    #[serde(default)]
+    pub seeding: Seeding,
+    /// Number of Swiss rounds (swiss only).
+    pub rounds: Option<usize>,
+    /// Games per bracket match; the first to win a majority advances (elimination only).
+    pub best_of: Option<usize>,
+    /// Keep players who have already met in different groups where possible
+    /// (defaults to on).
+    pub avoid_rematches: Option<bool>,
+    /// Keep the group winners of the previous stage in different groups
+    /// where possible.
+    pub separate_winners: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StageFormat {
+    /// Everyone in a group plays everyone else twice, once with each side.
+    #[default]
+    RoundRobin,
+    /// Entrants are paired by seed (1v2, 3v4, ...) to decide final places.
+    Placement,
+    /// A fixed number of rounds, each pairing players with similar scores
+    /// who have not met yet. One game per pairing.
+    Swiss,
+    /// Seeded knockout bracket; one series loss eliminates.
+    SingleElimination,
+    /// Seeded knockout bracket with a losers bracket; two series losses eliminate.
+    DoubleElimination,
+}
+
+impl StageFormat {
+    pub fn is_elimination(self) -> bool {
+        matches!(self, StageFormat::SingleElimination | StageFormat::DoubleElimination)
+    }
+
+    /// Whether entrants play in groups and are ranked by their standings.
+    pub fn is_grouped(self) -> bool {
+        matches!(self, StageFormat::RoundRobin | StageFormat::Swiss)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Seeding {
+    /// Shuffle the entrants and deal them into groups.
+    #[default]
+    Random,
+    /// Deal the entrants in ranking order, reversing direction every pass
+    /// (A B C D D C B A ...), so every group gets a similar spread.
+    Snake,
+    /// Split the ranking into pots of one player per group (the top seeds,
+    /// the next seeds, ...) and draw one player from every pot into each
+    /// group, so every group gets one player of each strength.
+    Pots,
+}
+
+fn default_groups() -> usize {
+    1
+}
+
+const DEFAULT_ADVANCE: usize = 2;
+
+impl StageConfig {
+    fn new(name: &str, format: StageFormat, groups: usize, advance: usize) -> Self {
+        StageConfig {
+            name: name.to_string(),
+            format,
+            groups,
+            advance: Some(advance),
+            seeding: Seeding::Random,
+            rounds: None,
+            best_of: None,
+            avoid_rematches: None,
+            separate_winners: None,
+        }
+    }
+
+    pub fn advance(&self) -> usize {
+        self.advance.unwrap_or(DEFAULT_ADVANCE)
+    }
+
+    pub fn best_of(&self) -> usize {
+        self.best_of.unwrap_or(1).max(1)
+    }
+
+    pub fn avoid_rematches(&self) -> bool {
+        self.avoid_rematches.unwrap_or(true)
+    }
+
+    pub fn separate_winners(&self) -> bool {
+        self.separate_winners.unwrap_or(false)
+    }
+}
+
+/// The classic five round layout used when the config has no `[[stages]]`:
+/// 8 groups -> 4 groups -> 2 groups -> 1 group -> championship and third place matches.
+pub fn default_stages() -> Vec<StageConfig> {
+    vec![
+        StageConfig::new("First Round", StageFormat::RoundRobin, 8, 2),
+        StageConfig::new("Second Round", StageFormat::RoundRobin, 4, 2),
+        StageConfig::new("Third Round", StageFormat::RoundRobin, 2, 2),
+        StageConfig::new("Fourth Round", StageFormat::RoundRobin, 1, 4),
+        StageConfig::new("Final Round", StageFormat::Placement, 1, 4),
+    ]
+}
+
+/// Group names run "Group A".."Group Z", then "Group AA", "Group AB", ...
+pub fn group_name(index: usize) -> String {
+    let mut label = String::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        label.insert(0, (b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    format!("Group {}", label)
+}
+
+/// Name of the match deciding places `2k+1` and `2k+2` in a placement stage.
+pub fn placement_group_name(index: usize) -> String {
+    match index {
+        0 => "Championship".to_string(),
+        1 => "Third Place Match".to_string(),
+        _ => format!("Place {}-{} Match", 2 * index + 1, 2 * index + 2),
+    }
+}
+
+/// Name of the group holding the playoff games of `group`.
+pub fn playoff_group_name(group: &str) -> String {
+    format!("{} Playoff", group)
+}
+
+/// Split `players` into `num_groups` groups whose sizes differ by at most one.
+///
+/// With `Seeding::Snake` and `Seeding::Pots` the input order is treated as
+/// a ranking; with `Seeding::Random` the players are shuffled first.
+pub fn distribute<R: Rng>(
+    players: &[String],
+    num_groups: usize,
+    seeding: Seeding,
+    rng: &mut R,
+) -> Vec<Vec<String>> {
+    let num_groups = num_groups.clamp(1, players.len().max(1));
+    let mut groups = vec![Vec::new(); num_groups];
+
+    match seeding {
+        Seeding::Random => {
+            let mut players = players.to_vec();
+            players.shuffle(rng);
+
+            // Groups with index < remainder get one extra player
+            let base_size = players.len() / num_groups;
+            let remainder = players.len() % num_groups;
+            let mut start = 0;
+            for (i, group) in groups.iter_mut().enumerate() {
+                let group_size = if i < remainder { base_size + 1 } else { base_size };
+                group.extend_from_slice(&players[start..start + group_size]);
+                start += group_size;
+            }
+        }
+        Seeding::Snake => {
+            for (i, player) in players.iter().enumerate() {
+                let pass = i / num_groups;
+                let offset = i % num_groups;
+                let index = if pass.is_multiple_of(2) { offset } else { num_groups - 1 - offset };
+                groups[index].push(player.clone());
+            }
+        }
+        Seeding::Pots => {
+            for pot in players.chunks(num_groups) {
+                // A short last pot goes to randomly drawn groups
+                let mut slots: Vec<usize> = (0..num_groups).collect();
+                slots.shuffle(rng);
+                let mut pot = pot.to_vec();
+                pot.shuffle(rng);
+                for (player, slot) in pot.into_iter().zip(slots) {
+                    groups[slot].push(player);
+                }
+            }
+        }
+    }
+
+    groups
+}
+
+/// Pairs of players drawn into the same group who should have been kept apart.
+#[derive(Debug, Default, PartialEq)]
+pub struct Clashes {
+    /// Players who have already played each other.
+    pub rematches: Vec<(String, String)>,
+    /// Players who are both in the set to keep apart.
+    pub together: Vec<(String, String)>,
+}
+
+/// Swap players between `groups` so that as few players who have already
+/// met (`met`) share a group, and after that as few players from `apart`.
+///
+/// Only players in the same pot (`pot_of`, missing players all share one)
+/// are swapped, so the groups keep their sizes and their spread of seeds.
+/// The best swap is made until none helps, which may stop short of the best
+/// possible grouping; whatever could not be avoided is returned.
+pub fn keep_apart(
+    groups: &mut [Vec<String>],
+    pot_of: &HashMap<String, usize>,
+    met: &HashSet<(String, String)>,
+    apart: &HashSet<String>,
+) -> Clashes {
+    let players: usize = groups.iter().map(Vec::len).sum();
+    // A rematch outweighs any number of players from `apart` meeting
+    let cost = |group: &[String]| -> usize {
+        let clashes = clashes(group, met, apart);
+        clashes.rematches.len() * (players * players + 1) + clashes.together.len()
+    };
+
+    loop {
+        let mut best: Option<(usize, usize, usize, usize, usize)> = None;
+        for first in 0..groups.len() {
+            for second in first + 1..groups.len() {
+                let before = cost(&groups[first]) + cost(&groups[second]);
+                // Later players first, so lower seeds move rather than the top seeds
+                for i in (0..groups[first].len()).rev() {
+                    for j in (0..groups[second].len()).rev() {
+                        if pot_of.get(&groups[first][i]) != pot_of.get(&groups[second][j]) {
+                            continue;
+                        }
+                        swap(groups, (first, i), (second, j));
+                        let after = cost(&groups[first]) + cost(&groups[second]);
+                        swap(groups, (first, i), (second, j));
+
+                        let gain = before.saturating_sub(after);
+                        if after < before && best.is_none_or(|(best_gain, ..)| gain > best_gain) {
+                            best = Some((gain, first, i, second, j));
+                        }
+                    }
+                }
+            }
+        }
+
+        match best {
+            Some((_, first, i, second, j)) => swap(groups, (first, i), (second, j)),
+            None => break,
+        }
+    }
+
+    groups.iter().fold(Clashes::default(), |mut all, group| {
+        let clashes = clashes(group, met, apart);
+        all.rematches.extend(clashes.rematches);
+        all.together.extend(clashes.together);
+        all
+    })
+}
+
+fn clashes(group: &[String], met: &HashSet<(String, String)>, apart: &HashSet<String>) -> Clashes {
+    let mut clashes = Clashes::default();
+    for (i, a) in group.iter().enumerate() {
+        for b in &group[i + 1..] {
+            let pair = if a < b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+            if apart.contains(a) && apart.contains(b) {
+                clashes.together.push(pair.clone());
+            }
+            if met.contains(&pair) {
+                clashes.rematches.push(pair);
+            }
+        }
+    }
+    clashes
+}
+
+fn swap(groups: &mut [Vec<String>], (first, i): (usize, usize), (second, j): (usize, usize)) {
+    let player = std::mem::take(&mut groups[first][i]);
+    groups[first][i] = std::mem::replace(&mut groups[second][j], player);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn uneven_fields_split_into_groups_one_apart() {
+        let players: Vec<String> = (1..=11).map(|seed| seed.to_string()).collect();
+        let groups = distribute(&players, 4, Seeding::Random, &mut StdRng::seed_from_u64(7));
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), [3, 3, 3, 2]);
+        let mut drawn: Vec<String> = groups.concat();
+        drawn.sort_by_key(|player| player.parse::<usize>().unwrap());
+        assert_eq!(drawn, players);
+
+        // Snake order: 1 2 3 | 6 5 4 | 7 8 9 | 11 10
+        let groups = distribute(&players, 3, Seeding::Snake, &mut StdRng::seed_from_u64(7));
+        assert_eq!(groups, [
+            vec!["1", "6", "7"],
+            vec!["2", "5", "8", "11"],
+            vec!["3", "4", "9", "10"],
+        ]);
+
+        // More groups than players leaves no group empty
+        let groups = distribute(&players[..3], 8, Seeding::Random, &mut StdRng::seed_from_u64(7));
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), [1, 1, 1]);
+    }
+
+    #[test]
+    fn default_stages_narrow_down_to_a_placement_final() {
+        let stages = default_stages();
+        let layout: Vec<(&str, StageFormat, usize, usize)> = stages.iter()
+            .map(|stage| (stage.name.as_str(), stage.format, stage.groups, stage.advance()))
+            .collect();
+        assert_eq!(layout, [
+            ("First Round", StageFormat::RoundRobin, 8, 2),
+            ("Second Round", StageFormat::RoundRobin, 4, 2),
+            ("Third Round", StageFormat::RoundRobin, 2, 2),
+            ("Fourth Round", StageFormat::RoundRobin, 1, 4),
+            ("Final Round", StageFormat::Placement, 1, 4),
+        ]);
+        assert!(stages.iter().all(|stage| stage.seeding == Seeding::Random));
+    }
+
+    #[test]
+    fn groups_and_placement_matches_are_named_in_order() {
+        let names: Vec<String> = [0, 1, 25, 26, 27, 701, 702].into_iter().map(group_name).collect();
+        assert_eq!(names, ["Group A", "Group B", "Group Z", "Group AA", "Group AB", "Group ZZ", "Group AAA"]);
+
+        let matches: Vec<String> = (0..4).map(placement_group_name).collect();
+        assert_eq!(matches, ["Championship", "Third Place Match", "Place 5-6 Match", "Place 7-8 Match"]);
+    }
+
+    #[test]
+    fn pots_give_every_group_one_player_of_each_strength() {
+        let ranking: Vec<String> = (1..=10).map(|seed| seed.to_string()).collect();
+        for seed in 0..20 {
+            let groups = distribute(&ranking, 4, Seeding::Pots, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), 10);
+            for group in &groups {
+                // One player from each of the pots 1-4, 5-8 and (for two groups) 9-10
+                let pots: Vec<usize> = group.iter().map(|player| (player.parse::<usize>().unwrap() - 1) / 4).collect();
+                assert_eq!(pots, (0..group.len()).collect::<Vec<_>>(), "{:?}", groups);
+            }
+        }
+    }
+
+    fn names(players: &[&str]) -> Vec<String> {
+        players.iter().map(|player| player.to_string()).collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> HashSet<(String, String)> {
+        pairs.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+    }
+
+    #[test]
+    fn keeps_players_who_met_apart() {
+        // a and b topped one group, c and d another; pots are (a, c) and (b, d)
+        let mut groups = vec![names(&["a", "b"]), names(&["c", "d"])];
+        let pot_of: HashMap<String, usize> = [("a", 0), ("c", 0), ("b", 1), ("d", 1)]
+            .iter().map(|&(player, pot)| (player.to_string(), pot)).collect();
+        let met = pairs(&[("a", "b"), ("c", "d")]);
+
+        let clashes = keep_apart(&mut groups, &pot_of, &met, &HashSet::new());
+        assert_eq!(clashes, Clashes::default());
+        assert_eq!(groups, [names(&["a", "d"]), names(&["c", "b"])]);
+
+        // Group winners a and c share a pot, so they stay apart as well
+        let apart: HashSet<String> = names(&["a", "c"]).into_iter().collect();
+        let mut groups = vec![names(&["a", "b"]), names(&["c", "d"])];
+        assert_eq!(keep_apart(&mut groups, &pot_of, &met, &apart), Clashes::default());
+    }
+
+    #[test]
+    fn reports_what_cannot_be_avoided() {
+        // Everyone has met everyone: the groups stay as they are
+        let mut groups = vec![names(&["a", "b"]), names(&["c", "d"])];
+        let met = pairs(&[("a", "b"), ("a", "c"), ("a", "d"), ("b", "c"), ("b", "d"), ("c", "d")]);
+        let apart: HashSet<String> = names(&["a", "b", "c"]).into_iter().collect();
+
+        let clashes = keep_apart(&mut groups, &HashMap::new(), &met, &apart);
+        assert_eq!(groups, [names(&["a", "b"]), names(&["c", "d"])]);
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(clashes.rematches, [pair("a", "b"), pair("c", "d")]);
+        assert_eq!(clashes.together, [pair("a", "b")]);
+    }
+}
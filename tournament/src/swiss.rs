@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+/// A player's standing going into a Swiss round.
+#[derive(Debug, Clone)]
+pub struct SwissEntry {
+    pub name: String,
+    pub score: f32,
+    pub opponents: HashSet<String>,
+    /// Games played as player 1 minus games played as player 2.
+    pub color_balance: i32,
+    /// Whether the player moved first in their most recent game.
+    pub last_was_first: Option<bool>,
+    pub had_bye: bool,
+}
+
+impl SwissEntry {
+    pub fn new(name: &str) -> Self {
+        SwissEntry {
+            name: name.to_string(),
+            score: 0.0,
+            opponents: HashSet::new(),
+            color_balance: 0,
+            last_was_first: None,
+            had_bye: false,
+        }
+    }
+}
+
+/// The games for one Swiss round. Each game is `(player1, player2)`.
+#[derive(Debug, Default)]
+pub struct Pairing {
+    pub games: Vec<(String, String)>,
+    pub bye: Option<String>,
+}
+
+/// Pair one Swiss round.
+///
+/// `entries` must be in seed order; players are then ranked by score (stable,
+/// so seeds break ties) and paired from the top down against the closest
+/// ranked opponent they have not met yet. If no such pairing exists, repeats
+/// are allowed rather than leaving players idle.
+pub fn pair_round(entries: &[SwissEntry]) -> Pairing {
+    let mut ranked: Vec<&SwissEntry> = entries.iter().collect();
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut pairing = Pairing::default();
+
+    // With an odd field the lowest ranked player without a bye sits out
+    if ranked.len() % 2 == 1 {
+        let index = ranked.iter().rposition(|entry| !entry.had_bye).unwrap_or(ranked.len() - 1);
+        pairing.bye = Some(ranked.remove(index).name.clone());
+    }
+
+    let pairs = pair_without_repeats(&ranked).unwrap_or_else(|| {
+        // Every player has met every possible opponent; fall back to plain score order
+        (0..ranked.len() / 2).map(|i| (2 * i, 2 * i + 1)).collect()
+    });
+
+    for (a, b) in pairs {
+        pairing.games.push(assign_colors(ranked[a], ranked[b]));
+    }
+
+    pairing
+}
+
+/// Backtracking search for a pairing with no rematches, preferring opponents
+/// with the closest rank. Returns index pairs into `ranked`.
+fn pair_without_repeats(ranked: &[&SwissEntry]) -> Option<Vec<(usize, usize)>> {
+    fn search(
+        ranked: &[&SwissEntry],
+        paired: &mut Vec<bool>,
+        pairs: &mut Vec<(usize, usize)>,
+    ) -> bool {
+        let Some(first) = paired.iter().position(|done| !done) else {
+            return true;
+        };
+        paired[first] = true;
+
+        for second in (first + 1)..ranked.len() {
+            if paired[second] || ranked[first].opponents.contains(&ranked[second].name) {
+                continue;
+            }
+            paired[second] = true;
+            pairs.push((first, second));
+            if search(ranked, paired, pairs) {
+                return true;
+            }
+            pairs.pop();
+            paired[second] = false;
+        }
+
+        paired[first] = false;
+        false
+    }
+
+    let mut paired = vec![false; ranked.len()];
+    let mut pairs = Vec::new();
+    search(ranked, &mut paired, &mut pairs).then_some(pairs)
+}
+
+/// Give the first move to whoever is owed it: the lower color balance, then
+/// whoever went second last time, then the higher ranked player.
+fn assign_colors(higher: &SwissEntry, lower: &SwissEntry) -> (String, String) {
+    let lower_goes_first = match lower.color_balance.cmp(&higher.color_balance) {
+        std::cmp::Ordering::Less => true,
+        std::cmp::Ordering::Greater => false,
+        std::cmp::Ordering::Equal => {
+            higher.last_was_first == Some(true) && lower.last_was_first != Some(true)
+        }
+    };
+
+    if lower_goes_first {
+        (lower.name.clone(), higher.name.clone())
+    } else {
+        (higher.name.clone(), lower.name.clone())
+    }
+}
+
+/// Default number of Swiss rounds: enough to separate a single winner.
+pub fn default_rounds(num_players: usize) -> usize {
+    let mut rounds = 0;
+    while (1usize << rounds) < num_players {
+        rounds += 1;
+    }
+    rounds.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: f32) -> SwissEntry {
+        SwissEntry { score, ..SwissEntry::new(name) }
+    }
+
+    #[test]
+    fn pairs_by_score_without_rematches() {
+        let mut a = entry("a", 2.0);
+        let mut b = entry("b", 2.0);
+        a.opponents.insert("b".to_string());
+        b.opponents.insert("a".to_string());
+        let entries = vec![a, b, entry("c", 0.0), entry("d", 0.0)];
+
+        let pairing = pair_round(&entries);
+        let mut games: Vec<(String, String)> = pairing.games.into_iter()
+            .map(|(x, y)| if x < y { (x, y) } else { (y, x) })
+            .collect();
+        games.sort();
+
+        assert_eq!(games, vec![("a".into(), "c".into()), ("b".into(), "d".into())]);
+        assert!(pairing.bye.is_none());
+    }
+
+    #[test]
+    fn bye_goes_to_lowest_player_without_one() {
+        let mut last = entry("c", 0.0);
+        last.had_bye = true;
+        let entries = vec![entry("a", 2.0), entry("b", 1.0), last];
+
+        let pairing = pair_round(&entries);
+        assert_eq!(pairing.bye.as_deref(), Some("b"));
+        assert_eq!(pairing.games.len(), 1);
+    }
+
+    #[test]
+    fn player_owed_first_move_gets_it() {
+        let mut a = entry("a", 1.0);
+        a.color_balance = 1;
+        a.last_was_first = Some(true);
+        let mut b = entry("b", 1.0);
+        b.color_balance = -1;
+        b.last_was_first = Some(false);
+
+        let pairing = pair_round(&[a, b]);
+        assert_eq!(pairing.games, vec![("b".to_string(), "a".to_string())]);
+    }
+
+    #[test]
+    fn default_round_count() {
+        assert_eq!(default_rounds(2), 1);
+        assert_eq!(default_rounds(8), 3);
+        assert_eq!(default_rounds(50), 6);
+    }
+}
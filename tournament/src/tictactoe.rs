@@ -0,0 +1,141 @@
+use crate::game::{Game, GameState, IllegalMove, Outcome};
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2], [3, 4, 5], [6, 7, 8], // rows
+    [0, 3, 6], [1, 4, 7], [2, 5, 8], // columns
+    [0, 4, 8], [2, 4, 6],            // diagonals
+];
+
+/// Square index for a move like "b2": columns a-c, rows 1-3.
+fn parse_square(text: &str) -> Option<usize> {
+    let text = text.to_ascii_lowercase();
+    let mut chars = text.chars();
+    let (column, row) = (chars.next()?, chars.next()?);
+    if chars.next().is_some() || !('a'..='c').contains(&column) || !('1'..='3').contains(&row) {
+        return None;
+    }
+    Some((row as usize - '1' as usize) * 3 + (column as usize - 'a' as usize))
+}
+
+fn is_winner(board: &[Option<usize>; 9], slot: usize) -> bool {
+    LINES.iter().any(|line| line.iter().all(|&square| board[square] == Some(slot)))
+}
+
+/// The Python referee's `tictactoe` game. Players are told "blue" (X, moves
+/// first) or "orange" (O) and then exchange squares like "b2", one per line.
+#[derive(Debug)]
+pub struct TicTacToe;
+
+impl Game for TicTacToe {
+    fn name(&self) -> &'static str {
+        "tictactoe"
+    }
+
+    fn player1_flag(&self) -> &'static str {
+        "--player"
+    }
+
+    fn color_aliases(&self) -> [&'static [&'static str]; 2] {
+        [&["x"], &["o"]]
+    }
+
+    fn new_state(&self) -> Box<dyn GameState> {
+        Box::new(State { board: [None; 9], turn: 0 })
+    }
+}
+
+struct State {
+    board: [Option<usize>; 9],
+    turn: usize,
+}
+
+impl GameState for State {
+    fn turn(&self) -> usize {
+        self.turn
+    }
+
+    fn play(&mut self, text: &str) -> Result<Option<Outcome>, IllegalMove> {
+        let square = parse_square(text)
+            .ok_or_else(|| IllegalMove { well_formed: false, reason: format!("'{}' is not a square", text) })?;
+        if self.board[square].is_some() {
+            return Err(IllegalMove { well_formed: true, reason: format!("Position {} is already occupied", text) });
+        }
+
+        let slot = self.turn;
+        self.board[square] = Some(slot);
+        self.turn = 1 - slot;
+
+        if is_winner(&self.board, slot) {
+            return Ok(Some((Some(slot), "three_in_a_row")));
+        }
+        if self.board.iter().all(Option::is_some) {
+            return Ok(Some((None, "board_full")));
+        }
+        Ok(None)
+    }
+
+    fn board(&self) -> String {
+        let rows: Vec<String> = (0..3).rev().map(|row| {
+            let squares: Vec<&str> = self.board[row * 3..row * 3 + 3].iter()
+                .map(|square| match square {
+                    Some(0) => "X",
+                    Some(_) => "O",
+                    None => ".",
+                })
+                .collect();
+            format!("{} {}", row + 1, squares.join(" "))
+        }).collect();
+        format!("{}\n  a b c", rows.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native;
+    use crate::runner::RefereeResult;
+    use std::time::Duration;
+
+    fn play(commands: [&str; 2], timeout: Duration) -> RefereeResult {
+        native::play(&TicTacToe, commands, timeout)
+    }
+
+    #[test]
+    fn parses_squares() {
+        assert_eq!(parse_square("a1"), Some(0));
+        assert_eq!(parse_square("C3"), Some(8));
+        assert_eq!(parse_square("b2"), Some(4));
+        assert_eq!(parse_square("d1"), None);
+        assert_eq!(parse_square("a"), None);
+        assert_eq!(parse_square("a12"), None);
+    }
+
+    #[test]
+    fn plays_a_game_between_scripted_agents() {
+        let blue = "sh -c 'read color; echo a1; read m; echo a2; read m; echo a3; read m'";
+        let orange = "sh -c 'read color; read m; echo b1; read m; echo b2; read m'";
+        let result = play([blue, orange], Duration::from_secs(5));
+        assert_eq!(result.winner.as_deref(), Some("player1"));
+        assert_eq!(result.termination, "three_in_a_row");
+        assert_eq!(result.moves, Some(5));
+    }
+
+    #[test]
+    fn occupied_square_and_garbage_are_forfeits() {
+        let blue = "sh -c 'read color; echo b2; read m; echo a1; read m'";
+        let result = play([blue, "sh -c 'read color; read m; echo B2; read m'"], Duration::from_secs(5));
+        assert_eq!((result.winner.as_deref(), result.termination.as_str()), (Some("player1"), "invalid_move"));
+
+        let result = play([blue, "sh -c 'read color; read m; echo hello; read m'"], Duration::from_secs(5));
+        assert_eq!(result.termination, "protocol_violation");
+
+        let result = play(["sh -c 'read color; exit 1'", blue], Duration::from_secs(5));
+        assert_eq!((result.winner.as_deref(), result.termination.as_str()), (Some("player2"), "crash"));
+    }
+
+    #[test]
+    fn slow_agent_times_out() {
+        let result = play(["sh -c 'read color; sleep 2'", "sh -c 'read color; read m'"], Duration::from_millis(200));
+        assert_eq!((result.winner.as_deref(), result.termination.as_str()), (Some("player2"), "timeout"));
+    }
+}
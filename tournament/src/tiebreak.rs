@@ -0,0 +1,311 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{MatchResult, PlayerStats, Scoring};
+
+/// One step of the chain that orders players level on points.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Tiebreak {
+    /// Points from the games between the tied players only.
+    HeadToHead,
+    /// Most games won (byes included).
+    Wins,
+    /// Points of every opponent beaten, plus half the points of every
+    /// opponent drawn, counted once per game.
+    SonnebornBerger,
+    /// Most games won as the player moving second.
+    WinsAsSecond,
+    /// Points from playoff games between the tied players. Playoffs are only
+    /// played when the tie decides who advances.
+    Playoff,
+    /// A coin flip drawn from the tournament seed, so a rerun flips the same way.
+    CoinFlip,
+}
+
+/// Used when `settings.tiebreaks` is not set.
+pub const DEFAULT_CHAIN: &[Tiebreak] = &[
+    Tiebreak::HeadToHead,
+    Tiebreak::Wins,
+    Tiebreak::SonnebornBerger,
+    Tiebreak::WinsAsSecond,
+    Tiebreak::CoinFlip,
+];
+
+impl Tiebreak {
+    pub fn label(self) -> &'static str {
+        match self {
+            Tiebreak::HeadToHead => "head-to-head",
+            Tiebreak::Wins => "wins",
+            Tiebreak::SonnebornBerger => "Sonneborn-Berger",
+            Tiebreak::WinsAsSecond => "wins as second",
+            Tiebreak::Playoff => "playoff",
+            Tiebreak::CoinFlip => "coin flip",
+        }
+    }
+}
+
+/// A player's place in the standings.
+#[derive(Debug)]
+pub struct Standing<'a> {
+    pub stats: &'a PlayerStats,
+    /// The tiebreaker that put this player ahead of the next one, when the
+    /// two were level on points.
+    pub decided_by: Option<Tiebreak>,
+}
+
+/// Orders players by points, then by the tiebreak chain.
+#[derive(Debug)]
+pub struct Tiebreaker<'a> {
+    chain: &'a [Tiebreak],
+    scoring: Scoring,
+    /// Every game of the round the players are ranked in.
+    games: &'a [MatchResult],
+    /// Playoff games of the round, which count for nothing but `Playoff`.
+    playoffs: &'a [MatchResult],
+    /// Points of every player in the round, for Sonneborn-Berger.
+    points: HashMap<&'a str, f32>,
+    round: &'a str,
+    seed: u64,
+}
+
+impl<'a> Tiebreaker<'a> {
+    pub fn new(
+        chain: &'a [Tiebreak],
+        scoring: Scoring,
+        round: &'a str,
+        players: impl IntoIterator<Item = &'a PlayerStats>,
+        games: &'a [MatchResult],
+        playoffs: &'a [MatchResult],
+        seed: u64,
+    ) -> Self {
+        let points = players.into_iter().map(|stats| (stats.name.as_str(), stats.points)).collect();
+        Tiebreaker { chain, scoring, games, playoffs, points, round, seed }
+    }
+
+    /// `players` best first. Ties the whole chain cannot break keep the
+    /// order they were given in.
+    pub fn rank(&self, players: impl IntoIterator<Item = &'a PlayerStats>) -> Vec<Standing<'a>> {
+        let mut standings: Vec<Standing<'a>> = players.into_iter()
+            .map(|stats| Standing { stats, decided_by: None })
+            .collect();
+        standings.sort_by(|a, b| b.stats.points.partial_cmp(&a.stats.points).unwrap_or(Ordering::Equal));
+
+        for tied in standings.chunk_by_mut(|a, b| a.stats.points == b.stats.points) {
+            self.break_ties(tied, self.chain);
+        }
+        standings
+    }
+
+    /// Order `tied` by the first tiebreaker of `chain` that tells them apart.
+    /// Players still level after a split start over from the top of the chain,
+    /// so head-to-head is recomputed among just them.
+    fn break_ties(&self, tied: &mut [Standing<'a>], chain: &[Tiebreak]) {
+        let Some((&tiebreak, rest)) = chain.split_first() else {
+            return;
+        };
+        if tied.len() < 2 {
+            return;
+        }
+
+        let names: Vec<&str> = tied.iter().map(|standing| standing.stats.name.as_str()).collect();
+        let values: HashMap<&str, f64> = tied.iter()
+            .map(|standing| (standing.stats.name.as_str(), self.value(tiebreak, standing.stats, &names)))
+            .collect();
+        let value = |standing: &Standing| values[standing.stats.name.as_str()];
+        tied.sort_by(|a, b| value(b).partial_cmp(&value(a)).unwrap_or(Ordering::Equal));
+
+        let mut runs: Vec<&mut [Standing<'a>]> = tied.chunk_by_mut(|a, b| value(a) == value(b)).collect();
+        if runs.len() == 1 {
+            self.break_ties(runs[0], rest);
+            return;
+        }
+
+        let last = runs.len() - 1;
+        for (index, run) in runs.iter_mut().enumerate() {
+            if index < last {
+                if let Some(standing) = run.last_mut() {
+                    standing.decided_by = Some(tiebreak);
+                }
+            }
+            self.break_ties(run, self.chain);
+        }
+    }
+
+    /// `stats`' score under `tiebreak`; higher is better. `tied` lists every
+    /// player level with them.
+    fn value(&self, tiebreak: Tiebreak, stats: &PlayerStats, tied: &[&str]) -> f64 {
+        let name = stats.name.as_str();
+        match tiebreak {
+            Tiebreak::HeadToHead => self.points_among(self.games, name, tied),
+            Tiebreak::Wins => f64::from(stats.wins),
+            Tiebreak::SonnebornBerger => self.games.iter()
+                .filter_map(|game| {
+                    let opponent = if game.player1 == name { &game.player2 } else { &game.player1 };
+                    let points = self.points.get(opponent.as_str()).copied().unwrap_or(0.0);
+                    game_score(game, name).map(|score| score * f64::from(points))
+                })
+                .sum(),
+            Tiebreak::WinsAsSecond => self.games.iter()
+                .filter(|game| game.player2 == name && game.winner.as_deref() == Some(name))
+                .count() as f64,
+            Tiebreak::Playoff => self.points_among(self.playoffs, name, tied),
+            Tiebreak::CoinFlip => {
+                let mut rng = StdRng::seed_from_u64(self.seed ^ fnv1a(&format!("{}/{}", self.round, name)));
+                f64::from(rng.gen::<u32>())
+            }
+        }
+    }
+
+    /// Points `name` scored in the `games` played between two of the `tied` players.
+    fn points_among(&self, games: &[MatchResult], name: &str, tied: &[&str]) -> f64 {
+        games.iter()
+            .filter(|game| tied.contains(&game.player1.as_str()) && tied.contains(&game.player2.as_str()))
+            .filter_map(|game| self.scoring.points(game, name))
+            .map(f64::from)
+            .sum()
+    }
+}
+
+/// The players around the cut line of `standings` that are level on points
+/// and that no tiebreaker has told apart, so the cut falls inside their tie.
+/// `cut` is the position of the best player who does not advance. Empty when
+/// the cut is clean.
+pub fn tied_at_cut<'a>(standings: &[Standing<'a>], cut: usize) -> Vec<&'a PlayerStats> {
+    // Whether standing `i` is still tied with the one below it
+    let level = |i: usize| {
+        standings.get(i + 1).is_some_and(|next| {
+            standings[i].decided_by.is_none() && standings[i].stats.points == next.stats.points
+        })
+    };
+    if cut == 0 || !level(cut - 1) {
+        return Vec::new();
+    }
+
+    let mut first = cut - 1;
+    while first > 0 && level(first - 1) {
+        first -= 1;
+    }
+    let mut last = cut;
+    while level(last) {
+        last += 1;
+    }
+    standings[first..=last].iter().map(|standing| standing.stats).collect()
+}
+
+/// 1 for a win, 0.5 for a draw and 0 for a loss; `None` if `player` did not
+/// take part or the game did not count.
+fn game_score(game: &MatchResult, player: &str) -> Option<f64> {
+    if (game.player1 != player && game.player2 != player) || game.is_no_contest() {
+        return None;
+    }
+    Some(if game.is_draw {
+        0.5
+    } else if game.winner.as_deref() == Some(player) {
+        1.0
+    } else {
+        0.0
+    })
+}
+
+/// A hash that stays the same across builds, unlike `DefaultHasher`.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(games: &[MatchResult], names: &[&str]) -> Vec<PlayerStats> {
+        names.iter().map(|name| {
+            let mut stats = PlayerStats::new(name);
+            for game in games.iter().filter(|game| game.player1 == *name || game.player2 == *name) {
+                stats.add_result(game, &Scoring::default());
+            }
+            stats
+        }).collect()
+    }
+
+    fn ranked<'a>(tiebreaker: &Tiebreaker<'a>, stats: &'a [PlayerStats]) -> Vec<(String, Option<Tiebreak>)> {
+        tiebreaker.rank(stats).into_iter()
+            .map(|standing| (standing.stats.name.clone(), standing.decided_by))
+            .collect()
+    }
+
+    #[test]
+    fn head_to_head_then_the_rest_of_the_chain() {
+        // a, b and c all finish on 6 points. Among the three c scores best;
+        // a and b are level until only their own games are counted
+        let games = vec![
+            MatchResult::test("a", "b", Some("a")), MatchResult::test("b", "a", Some("a")),
+            MatchResult::test("c", "a", Some("c")), MatchResult::test("c", "a", Some("c")),
+            MatchResult::test("b", "c", Some("b")), MatchResult::test("b", "c", Some("b")), MatchResult::test("b", "c", Some("c")),
+            MatchResult::test("a", "d", Some("a")), MatchResult::test("b", "d", Some("b")),
+        ];
+        let stats = stats(&games, &["a", "b", "c", "d"]);
+        let tiebreaker = Tiebreaker::new(DEFAULT_CHAIN, Scoring::default(), "Round 1", &stats, &games, &[], 7);
+        assert_eq!(ranked(&tiebreaker, &stats[..3]), vec![
+            ("c".to_string(), Some(Tiebreak::HeadToHead)),
+            ("a".to_string(), Some(Tiebreak::HeadToHead)),
+            ("b".to_string(), None),
+        ]);
+
+        // a and c each won once as the second player; nothing splits them
+        let chain = [Tiebreak::WinsAsSecond];
+        let tiebreaker = Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &[], 7);
+        assert_eq!(ranked(&tiebreaker, &stats[..3]), vec![
+            ("a".to_string(), None),
+            ("c".to_string(), Some(Tiebreak::WinsAsSecond)),
+            ("b".to_string(), None),
+        ]);
+    }
+
+    #[test]
+    fn sonneborn_berger_and_coin_flip() {
+        // a beat the strong player, b beat the weak one
+        let games = vec![
+            MatchResult::test("a", "strong", Some("a")), MatchResult::test("b", "weak", Some("b")),
+            MatchResult::test("strong", "weak", Some("strong")),
+        ];
+        let stats = stats(&games, &["a", "b", "strong", "weak"]);
+        let chain = [Tiebreak::SonnebornBerger];
+        let tiebreaker = Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &[], 7);
+        assert_eq!(ranked(&tiebreaker, &stats[..2])[0], ("a".to_string(), Some(Tiebreak::SonnebornBerger)));
+
+        // The same seed always flips the same way
+        let chain = [Tiebreak::CoinFlip];
+        let first = ranked(&Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &[], 7), &stats[..2]);
+        let reversed = [stats[1].clone(), stats[0].clone()];
+        assert_eq!(ranked(&Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &[], 7), &reversed), first);
+        assert_eq!(first[0].1, Some(Tiebreak::CoinFlip));
+    }
+
+    #[test]
+    fn playoffs_settle_ties_on_the_cut() {
+        // Three players on 2 points, none of them told apart by head-to-head
+        let games = vec![MatchResult::test("a", "b", Some("a")), MatchResult::test("b", "c", Some("b")), MatchResult::test("c", "a", Some("c"))];
+        let stats = stats(&games, &["a", "b", "c", "d"]);
+        let chain = [Tiebreak::HeadToHead, Tiebreak::Playoff];
+        let tiebreaker = Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &[], 7);
+        let names = |players: Vec<&PlayerStats>| players.iter().map(|stats| stats.name.clone()).collect::<Vec<_>>();
+
+        // Top two advance: a, b and c all need a playoff; a clean cut needs none
+        assert_eq!(names(tied_at_cut(&tiebreaker.rank(&stats), 2)), vec!["a", "b", "c"]);
+        assert!(tied_at_cut(&tiebreaker.rank(&stats), 3).is_empty());
+
+        // c wins both playoff games and b beats a
+        let playoffs = vec![MatchResult::test("c", "a", Some("c")), MatchResult::test("b", "c", Some("c")), MatchResult::test("a", "b", Some("b"))];
+        let tiebreaker = Tiebreaker::new(&chain, Scoring::default(), "Round 1", &stats, &games, &playoffs, 7);
+        let standings = tiebreaker.rank(&stats);
+        assert!(tied_at_cut(&standings, 2).is_empty());
+        assert_eq!(ranked(&tiebreaker, &stats)[..3], [
+            ("c".to_string(), Some(Tiebreak::Playoff)),
+            ("b".to_string(), Some(Tiebreak::Playoff)),
+            ("a".to_string(), None),
+        ]);
+    }
+}
@@ -0,0 +1,114 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::game::Game;
+use crate::runner::MoveRecord;
+use crate::MatchResult;
+
+/// The moves of one game, as reported by the referee or the native engine.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct History {
+    pub moves: Vec<MoveRecord>,
+    /// The move that lost the game by forfeit, if the player sent one.
+    pub rejected_move: Option<MoveRecord>,
+}
+
+/// Everything recorded about one game, saved as a JSON file so results can
+/// be checked and games replayed later.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transcript {
+    pub version: u32,
+    pub game: String,
+    pub result: MatchResult,
+    #[serde(flatten)]
+    pub history: History,
+    /// The board after the last move, drawn by replaying the moves with the
+    /// native rules.
+    pub board: Option<String>,
+}
+
+impl Transcript {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{} is not a game transcript: {}", path, e))
+    }
+
+    pub fn new(game: &dyn Game, result: &MatchResult, history: History) -> Self {
+        let board = final_board(game, &history.moves);
+        Transcript { version: 1, game: game.name().to_string(), result: result.clone(), history, board }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+}
+
+/// Where the transcript of a game is saved: `<dir>/<round>/<group>/game_<n>.json`.
+pub fn path(dir: &Path, round: &str, group: &str, game_number: i32) -> PathBuf {
+    dir.join(file_name(round)).join(file_name(group)).join(format!("game_{}.json", game_number))
+}
+
+/// `name` with anything but letters, digits, '-' and '_' replaced by '_'.
+fn file_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+/// Replay `moves` with the native rules to draw the final board. If the rules
+/// reject a move, or end the game before the last one, the transcript does
+/// not match the game and no board is drawn.
+fn final_board(game: &dyn Game, moves: &[MoveRecord]) -> Option<String> {
+    let mut state = game.new_state();
+    for (number, record) in moves.iter().enumerate() {
+        match state.play(&record.text) {
+            Err(illegal) => {
+                warn!("Move {} ({}) of a {} transcript is illegal under the native rules: {}",
+                      number + 1, record.text, game.name(), illegal.reason);
+                return None;
+            }
+            Ok(Some((_, termination))) if number + 1 < moves.len() => {
+                warn!("A {} transcript goes on after move {}, which ends the game under the native rules ({})",
+                      game.name(), number + 1, termination);
+                return None;
+            }
+            Ok(_) => {}
+        }
+    }
+    Some(state.board())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game;
+
+    fn record(player: &str, text: &str) -> MoveRecord {
+        MoveRecord { player: player.to_string(), text: text.to_string(), time: Some(0.1) }
+    }
+
+    #[test]
+    fn paths_are_safe_file_names() {
+        let path = path(Path::new("transcripts"), "Quarter Finals", "Group A/B", 3);
+        assert_eq!(path, Path::new("transcripts/Quarter_Finals/Group_A_B/game_3.json"));
+    }
+
+    #[test]
+    fn final_board_replays_the_moves() {
+        let tictactoe = game::find("tictactoe").unwrap();
+        let moves = [record("player1", "a1"), record("player2", "b2"), record("player1", "c3")];
+        assert_eq!(final_board(tictactoe, &moves).unwrap(), "3 . . X\n2 . O .\n1 X . .\n  a b c");
+        assert_eq!(final_board(tictactoe, &[record("player1", "a1"), record("player2", "a1")]), None);
+
+        // Moves after the game was won do not match the rules either
+        let won = ["a1", "b1", "a2", "b2", "a3", "c3"].iter().enumerate()
+            .map(|(index, text)| record(&format!("player{}", index % 2 + 1), text))
+            .collect::<Vec<_>>();
+        assert!(final_board(tictactoe, &won[..5]).is_some());
+        assert_eq!(final_board(tictactoe, &won), None);
+    }
+}
@@ -0,0 +1,161 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use crate::rating::{starting_table, Rating};
+use crate::MatchResult;
+
+/// Belief about one agent's skill: a normal distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Skill {
+    mu: f64,
+    sigma: f64,
+}
+
+/// TrueSkill-style ratings from `games`, updated one game at a time.
+///
+/// Performance varies around skill by `beta` (half the starting deviation),
+/// and skills drift by a hundredth of the starting deviation before every
+/// game, so agents never become completely certain. The chance of a draw is
+/// taken from the games themselves.
+pub fn rate(players: &[String], games: &[&MatchResult], initial_rating: f64, initial_deviation: f64) -> Vec<Rating> {
+    let beta = initial_deviation / 2.0;
+    let drift = initial_deviation / 100.0;
+    let draws = games.iter().filter(|game| game.is_draw).count();
+    let draw_probability = (draws as f64 / games.len().max(1) as f64).clamp(0.01, 0.9);
+    let draw_margin = inverse_cdf((draw_probability + 1.0) / 2.0) * SQRT_2 * beta;
+
+    let mut ratings = starting_table(players, games, Skill { mu: initial_rating, sigma: initial_deviation });
+
+    for game in games {
+        let mut first = ratings[&game.player1].0;
+        let mut second = ratings[&game.player2].0;
+        first.sigma = first.sigma.hypot(drift);
+        second.sigma = second.sigma.hypot(drift);
+
+        // Put the winner first; a draw keeps the order
+        let swapped = game.winner.as_deref() == Some(game.player2.as_str());
+        if swapped {
+            std::mem::swap(&mut first, &mut second);
+        }
+
+        let c = (2.0 * beta * beta + first.sigma * first.sigma + second.sigma * second.sigma).sqrt();
+        let t = (first.mu - second.mu) / c;
+        let margin = draw_margin / c;
+        let (v, w) = if game.is_draw { draw_factors(t, margin) } else { win_factors(t, margin) };
+
+        for (skill, sign) in [(&mut first, 1.0), (&mut second, -1.0)] {
+            let variance = skill.sigma * skill.sigma;
+            skill.mu += sign * variance / c * v;
+            skill.sigma = (variance * (1.0 - variance / (c * c) * w)).max(0.0).sqrt();
+        }
+
+        if swapped {
+            std::mem::swap(&mut first, &mut second);
+        }
+        for (player, skill) in [(&game.player1, first), (&game.player2, second)] {
+            if let Some(entry) = ratings.get_mut(player) {
+                entry.0 = skill;
+                entry.1 += 1;
+            }
+        }
+    }
+
+    ratings.into_iter()
+        .map(|(player, (skill, games))| Rating { rank: 0, player, rating: skill.mu, deviation: Some(skill.sigma), games })
+        .collect()
+}
+
+/// Mean and variance corrections after the first player beat the second,
+/// their performance gap being `t` and the draw margin `margin` (both in
+/// units of the combined deviation).
+fn win_factors(t: f64, margin: f64) -> (f64, f64) {
+    let x = t - margin;
+    let denominator = cdf(x);
+    let v = if denominator > 0.0 { pdf(x) / denominator } else { -x };
+    (v, clamp_w(v * (v + x)))
+}
+
+/// Corrections after a draw, as in [`win_factors`].
+fn draw_factors(t: f64, margin: f64) -> (f64, f64) {
+    let sign = if t < 0.0 { -1.0 } else { 1.0 };
+    let (a, b) = (margin - t.abs(), -margin - t.abs());
+    let denominator = cdf(a) - cdf(b);
+    if denominator <= 0.0 {
+        return (sign * a, 1.0);
+    }
+    let v = (pdf(b) - pdf(a)) / denominator;
+    let w = v * v + (a * pdf(a) - b * pdf(b)) / denominator;
+    (sign * v, clamp_w(w))
+}
+
+/// The variance factor must stay in [0, 1) for the deviation to shrink
+/// without going negative; rounding can push it slightly outside.
+fn clamp_w(w: f64) -> f64 {
+    if w.is_finite() { w.clamp(0.0, 1.0 - f64::EPSILON) } else { 0.0 }
+}
+
+fn pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn cdf(x: f64) -> f64 {
+    erfc(-x / SQRT_2) / 2.0
+}
+
+/// Complementary error function, accurate to about 1e-7 (Numerical Recipes' `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + z / 2.0);
+    let coefficients = [
+        -1.265_512_23, 1.000_023_68, 0.374_091_96, 0.096_784_18, -0.186_288_06,
+        0.278_868_07, -1.135_203_98, 1.488_515_87, -0.822_152_23, 0.170_872_77,
+    ];
+    let polynomial = coefficients.iter().rev().fold(0.0, |sum, coefficient| coefficient + t * sum);
+    let result = t * (-z * z + polynomial).exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+/// `x` with `cdf(x) == p`, by bisection.
+fn inverse_cdf(p: f64) -> f64 {
+    let (mut low, mut high) = (-10.0, 10.0);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if cdf(middle) < p {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_distribution_helpers() {
+        assert!((cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((inverse_cdf(0.975) - 1.96).abs() < 1e-3);
+    }
+
+    #[test]
+    fn a_win_moves_both_players_and_shrinks_their_deviation() {
+        // Two fresh players at the usual 25 / 8.333 with a 10% draw margin:
+        // reference TrueSkill gives 29.396 / 7.171 and 20.604 / 7.171
+        let beta = 25.0 / 6.0;
+        let margin = inverse_cdf(0.55) * SQRT_2 * beta;
+        let sigma = (25.0f64 / 3.0).hypot(25.0 / 300.0);
+        let c = (2.0 * beta * beta + 2.0 * sigma * sigma).sqrt();
+        let (v, w) = win_factors(0.0, margin / c);
+
+        let mu = 25.0 + sigma * sigma / c * v;
+        let deviation = (sigma * sigma * (1.0 - sigma * sigma / (c * c) * w)).sqrt();
+        assert!((mu - 29.396).abs() < 0.001, "{}", mu);
+        assert!((deviation - 7.171).abs() < 0.001, "{}", deviation);
+
+        // A draw between equals leaves the means alone
+        let (v, w) = draw_factors(0.0, margin / c);
+        assert!(v.abs() < 1e-12 && w > 0.0 && w < 1.0);
+    }
+}
@@ -0,0 +1,710 @@
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::agent;
+use crate::game;
+use crate::rating::RatingModel;
+use crate::stage::StageFormat;
+use crate::vars;
+use crate::tiebreak::Tiebreak;
+use crate::{Engine, TournamentConfig};
+
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "game", "settings", "groups", "agents", "stages", "output", "scoring", "ratings", "ladder", "seeds",
+];
+const SETTINGS_KEYS: &[&str] = &[
+    "timeout", "visual", "random_assignment", "debug", "port", "port_range", "seed", "parallelism", "engine", "tiebreaks",
+    "playoff_games",
+];
+const OUTPUT_KEYS: &[&str] = &["results", "checkpoint", "transcripts", "ratings"];
+const SCORING_KEYS: &[&str] = &["win", "draw", "loss", "forfeit"];
+const RATINGS_KEYS: &[&str] = &["model", "k_factor", "initial_rating", "initial_deviation", "tau"];
+const LADDER_KEYS: &[&str] = &["path", "event", "seed_groups"];
+const STAGE_KEYS: &[&str] = &[
+    "name", "format", "groups", "advance", "seeding", "rounds", "best_of", "avoid_rematches", "separate_winners",
+];
+
+/// One thing wrong with the configuration, pointing at the key responsible.
+#[derive(Debug)]
+pub struct Problem {
+    pub key: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, self.key, self.message),
+            None => write!(f, "{}: {}", self.key, self.message),
+        }
+    }
+}
+
+/// Collects problems and looks up the line each key was written on.
+struct Report<'a> {
+    text: &'a str,
+    problems: Vec<Problem>,
+}
+
+impl Report<'_> {
+    /// `table` is the dotted table the key lives in ("" for the top level),
+    /// with `index` selecting one entry of an array of tables like `[[stages]]`.
+    fn add(&mut self, table: &str, index: Option<usize>, key: Option<&str>, message: String) {
+        let mut path = table.to_string();
+        if let Some(index) = index {
+            path.push_str(&format!("[{}]", index));
+        }
+        if let Some(key) = key {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                path.push_str(key);
+            } else {
+                path.push_str(&format!("\"{}\"", key));
+            }
+        }
+
+        self.problems.push(Problem {
+            key: path,
+            line: find_line(self.text, table, index, key),
+            message,
+        });
+    }
+}
+
+/// Line (1-based) where `key` is set inside `table`, or where the table
+/// starts if the key is not written out. Only plain `[table]` /
+/// `[[table]]` headers and `key = value` lines are understood; anything
+/// fancier just yields no line number.
+fn find_line(text: &str, table: &str, index: Option<usize>, key: Option<&str>) -> Option<usize> {
+    let mut current = String::new();
+    let mut occurrence = 0;
+    let mut table_line = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        let header = if let Some(rest) = line.strip_prefix("[[") {
+            rest.split("]]").next()
+        } else if let Some(rest) = line.strip_prefix('[') {
+            rest.split(']').next()
+        } else {
+            None
+        };
+        if let Some(name) = header {
+            let name = name.trim();
+            occurrence = if name == current { occurrence + 1 } else { 1 };
+            current = name.to_string();
+        }
+
+        let in_table = current == table && index.is_none_or(|index| occurrence == index + 1);
+        if !in_table {
+            continue;
+        }
+        if header.is_some() {
+            table_line = Some(number + 1);
+        } else if let (Some(key), Some((name, _))) = (key, line.split_once('=')) {
+            if name.trim().trim_matches('"') == key {
+                return Some(number + 1);
+            }
+        }
+    }
+
+    table_line
+}
+
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Parse and check a tournament configuration, returning every problem found
+/// rather than stopping at the first. With `check_executables` the program
+/// each agent runs must also exist.
+pub fn validate(text: &str, check_executables: bool) -> Result<TournamentConfig, Vec<Problem>> {
+    let mut report = Report { text, problems: Vec::new() };
+
+    // Syntax errors make everything else meaningless
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(e) => {
+            return Err(vec![Problem {
+                key: "(syntax)".to_string(),
+                line: e.span().map(|span| line_at(text, span.start)),
+                message: e.message().to_string(),
+            }]);
+        }
+    };
+
+    check_unknown_keys(&mut report, &table);
+
+    let mut config: TournamentConfig = match toml::from_str(text) {
+        Ok(config) => config,
+        Err(e) => {
+            report.problems.push(Problem {
+                key: "(config)".to_string(),
+                line: e.span().map(|span| line_at(text, span.start)),
+                message: e.message().trim().to_string(),
+            });
+            return Err(report.problems);
+        }
+    };
+
+    expand_variables(&mut report, &mut config);
+    check_game(&mut report, &config);
+    check_agents(&mut report, &config, check_executables);
+    check_groups(&mut report, &config);
+    check_seeds(&mut report, &config);
+    check_settings(&mut report, &config);
+    check_scoring(&mut report, &config);
+    check_ratings(&mut report, &config);
+    check_ladder(&mut report, &config);
+    check_stages(&mut report, &config);
+
+    if report.problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(report.problems)
+    }
+}
+
+fn check_unknown_keys(report: &mut Report, table: &toml::Table) {
+    let known = |report: &mut Report, table_name: &str, index: Option<usize>, value: &toml::Table, keys: &[&str]| {
+        for key in value.keys().filter(|key| !keys.contains(&key.as_str())) {
+            report.add(table_name, index, Some(key), format!("unknown setting (expected one of: {})", keys.join(", ")));
+        }
+    };
+
+    known(report, "", None, table, TOP_LEVEL_KEYS);
+    if let Some(settings) = table.get("settings").and_then(|value| value.as_table()) {
+        known(report, "settings", None, settings, SETTINGS_KEYS);
+    }
+    if let Some(output) = table.get("output").and_then(|value| value.as_table()) {
+        known(report, "output", None, output, OUTPUT_KEYS);
+    }
+    if let Some(scoring) = table.get("scoring").and_then(|value| value.as_table()) {
+        known(report, "scoring", None, scoring, SCORING_KEYS);
+    }
+    if let Some(ratings) = table.get("ratings").and_then(|value| value.as_table()) {
+        known(report, "ratings", None, ratings, RATINGS_KEYS);
+    }
+    if let Some(ladder) = table.get("ladder").and_then(|value| value.as_table()) {
+        known(report, "ladder", None, ladder, LADDER_KEYS);
+    }
+    if let Some(stages) = table.get("stages").and_then(|value| value.as_array()) {
+        for (index, stage) in stages.iter().enumerate() {
+            if let Some(stage) = stage.as_table() {
+                known(report, "stages", Some(index), stage, STAGE_KEYS);
+            }
+        }
+    }
+}
+
+/// Substitute environment variables in agent names and commands, group names
+/// and members, seeded agents, and output and ladder paths, so one config works on every
+/// machine.
+fn expand_variables(report: &mut Report, config: &mut TournamentConfig) {
+    let mut expand = |table: &str, key: &str, value: &str| -> String {
+        vars::expand_env(value).unwrap_or_else(|message| {
+            report.add(table, None, Some(key), message);
+            value.to_string()
+        })
+    };
+
+    config.agents = config.agents.iter()
+        .map(|(name, command)| (expand("agents", name, name), expand("agents", name, command)))
+        .collect();
+
+    if let Some(groups) = &config.groups {
+        let expanded = groups.iter()
+            .map(|(group, players)| {
+                let players = players.iter().map(|player| expand("groups", group, player)).collect();
+                (expand("groups", group, group), players)
+            })
+            .collect();
+        config.groups = Some(expanded);
+    }
+
+    if let Some(seeds) = &config.seeds {
+        config.seeds = Some(seeds.iter().map(|(player, &seed)| (expand("seeds", player, player), seed)).collect());
+    }
+
+    if let Some(output) = &mut config.output {
+        for (key, path) in [
+            ("results", &mut output.results),
+            ("checkpoint", &mut output.checkpoint),
+            ("transcripts", &mut output.transcripts),
+            ("ratings", &mut output.ratings),
+        ] {
+            if let Some(path) = path {
+                *path = expand("output", key, path);
+            }
+        }
+    }
+
+    if let Some(ladder) = &mut config.ladder {
+        ladder.path = expand("ladder", "path", &ladder.path);
+    }
+}
+
+fn check_game(report: &mut Report, config: &TournamentConfig) {
+    if game::find(&config.game).is_none() {
+        report.add("", None, Some("game"), format!(
+            "unsupported game type '{}' (expected {})", config.game, game::names()
+        ));
+    }
+}
+
+fn check_agents(report: &mut Report, config: &TournamentConfig, check_executables: bool) {
+    if config.agents.len() < 2 {
+        report.add("agents", None, None, format!("at least 2 agents are needed, found {}", config.agents.len()));
+    }
+
+    for (name, command) in &config.agents {
+        // Split the way the agent will be started, so quoted paths stay whole
+        let Ok(words) = agent::split_command(command) else {
+            report.add("agents", None, Some(name), "command has an unterminated quote".to_string());
+            continue;
+        };
+        let Some(program) = words.first() else {
+            report.add("agents", None, Some(name), "command is empty".to_string());
+            continue;
+        };
+        if check_executables && !program_exists(program) {
+            report.add("agents", None, Some(name), format!(
+                "'{}' was not found (use --skip-validation to start anyway)", program
+            ));
+        }
+    }
+}
+
+/// A program given as a path must exist; a bare name must be on the PATH.
+fn program_exists(program: &str) -> bool {
+    if program.contains('/') || program.contains('\\') {
+        return Path::new(program).exists();
+    }
+    let Some(paths) = env::var_os("PATH") else {
+        return false;
+    };
+    env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
+fn check_groups(report: &mut Report, config: &TournamentConfig) {
+    let Some(groups) = &config.groups else {
+        return;
+    };
+
+    let mut assigned: BTreeMap<&str, &str> = BTreeMap::new();
+    for (group, players) in groups {
+        if players.len() < 2 {
+            report.add("groups", None, Some(group), format!("needs at least 2 players, has {}", players.len()));
+        }
+        for player in players {
+            if !config.agents.contains_key(player) {
+                report.add("groups", None, Some(group), format!("unknown player '{}' (not listed under [agents])", player));
+            }
+            match assigned.insert(player, group) {
+                Some(other) if other == group => {
+                    report.add("groups", None, Some(group), format!("'{}' is listed twice", player));
+                }
+                Some(other) => {
+                    report.add("groups", None, Some(group), format!("'{}' is already assigned to {}", player, other));
+                }
+                None => {}
+            }
+        }
+    }
+
+    for name in config.agents.keys().filter(|name| !assigned.contains_key(name.as_str())) {
+        report.add("groups", None, None, format!("agent '{}' is not assigned to any group", name));
+    }
+}
+
+fn check_seeds(report: &mut Report, config: &TournamentConfig) {
+    let Some(seeds) = &config.seeds else {
+        return;
+    };
+
+    let mut taken: BTreeMap<u32, &str> = BTreeMap::new();
+    for (player, &seed) in seeds {
+        if !config.agents.contains_key(player) {
+            report.add("seeds", None, Some(player), format!("unknown player '{}' (not listed under [agents])", player));
+        }
+        if seed == 0 {
+            report.add("seeds", None, Some(player), "must be at least 1".to_string());
+        } else if let Some(other) = taken.insert(seed, player) {
+            report.add("seeds", None, Some(player), format!("seed {} is already given to {}", seed, other));
+        }
+    }
+
+    let first_stage_is_bracket = config.stages.as_ref()
+        .and_then(|stages| stages.first())
+        .is_some_and(|stage| stage.format.is_elimination());
+    if config.groups.is_some() && !first_stage_is_bracket {
+        report.add("", None, Some("seeds"), "cannot seed the first stage when [groups] are predefined".to_string());
+    }
+    if config.ladder.as_ref().is_some_and(|ladder| ladder.seed_groups) {
+        report.add("ladder", None, Some("seed_groups"), "cannot seed from both the ladder and [seeds]".to_string());
+    }
+}
+
+fn check_settings(report: &mut Report, config: &TournamentConfig) {
+    let Some(settings) = &config.settings else {
+        return;
+    };
+
+    if let Some(timeout) = settings.timeout {
+        if timeout <= 0 {
+            report.add("settings", None, Some("timeout"), format!("must be at least 1 second, got {}", timeout));
+        }
+    }
+    if settings.port == Some(0) {
+        report.add("settings", None, Some("port"), "must be between 1 and 65535".to_string());
+    }
+    if let Some((first, last)) = settings.port_range {
+        if first == 0 || first > last {
+            report.add("settings", None, Some("port_range"), format!(
+                "[{}, {}] is not a valid range; give the first and last port, first <= last", first, last
+            ));
+        }
+    }
+    if settings.parallelism == Some(0) {
+        report.add("settings", None, Some("parallelism"), "must be at least 1".to_string());
+    }
+    if settings.engine == Some(Engine::Native) && settings.visual == Some(true) {
+        report.add("settings", None, Some("engine"),
+                   "the native engine cannot show games; set visual = false or use the referee".to_string());
+    }
+    if let Some(tiebreaks) = &settings.tiebreaks {
+        for (index, tiebreak) in tiebreaks.iter().enumerate() {
+            if tiebreaks[..index].contains(tiebreak) {
+                report.add("settings", None, Some("tiebreaks"), format!("'{}' is listed twice", tiebreak.label()));
+            }
+        }
+    }
+    if settings.playoff_games == Some(0) {
+        report.add("settings", None, Some("playoff_games"), "must be at least 1".to_string());
+    } else if settings.playoff_games.is_some()
+        && !settings.tiebreaks.as_ref().is_some_and(|tiebreaks| tiebreaks.contains(&Tiebreak::Playoff))
+    {
+        report.add("settings", None, Some("playoff_games"),
+                   "has no effect unless \"playoff\" is listed in tiebreaks".to_string());
+    }
+}
+
+fn check_scoring(report: &mut Report, config: &TournamentConfig) {
+    let Some(scoring) = &config.scoring else {
+        return;
+    };
+
+    if scoring.win <= scoring.loss {
+        report.add("scoring", None, Some("win"), format!(
+            "a win ({}) must be worth more than a loss ({})", scoring.win, scoring.loss
+        ));
+    } else if scoring.draw < scoring.loss || scoring.draw > scoring.win {
+        report.add("scoring", None, Some("draw"), format!(
+            "a draw ({}) must be worth between a loss ({}) and a win ({})", scoring.draw, scoring.loss, scoring.win
+        ));
+    }
+    if let Some(forfeit) = scoring.forfeit.filter(|&forfeit| forfeit > scoring.loss) {
+        report.add("scoring", None, Some("forfeit"), format!(
+            "a forfeit ({}) must not be worth more than a loss ({})", forfeit, scoring.loss
+        ));
+    }
+}
+
+fn check_ratings(report: &mut Report, config: &TournamentConfig) {
+    let Some(ratings) = &config.ratings else {
+        return;
+    };
+
+    for (key, value, models) in [
+        ("k_factor", ratings.k_factor, &[RatingModel::Elo][..]),
+        ("initial_deviation", ratings.initial_deviation, &[RatingModel::Glicko2, RatingModel::Trueskill][..]),
+        ("tau", ratings.tau, &[RatingModel::Glicko2][..]),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+        if !models.contains(&ratings.model) {
+            report.add("ratings", None, Some(key), format!("does not apply to the {} model", ratings.describe()));
+        } else if value <= 0.0 {
+            report.add("ratings", None, Some(key), format!("must be greater than 0, got {}", value));
+        }
+    }
+}
+
+fn check_ladder(report: &mut Report, config: &TournamentConfig) {
+    let Some(ladder) = &config.ladder else {
+        return;
+    };
+
+    if ladder.path.trim().is_empty() {
+        report.add("ladder", None, Some("path"), "must not be empty".to_string());
+    }
+    if ladder.event.as_ref().is_some_and(|event| event.trim().is_empty()) {
+        report.add("ladder", None, Some("event"), "must not be empty".to_string());
+    }
+    if ladder.seed_groups && config.groups.is_some() {
+        report.add("ladder", None, Some("seed_groups"), "cannot seed from the ladder when [groups] are predefined".to_string());
+    }
+}
+
+fn check_stages(report: &mut Report, config: &TournamentConfig) {
+    let Some(stages) = &config.stages else {
+        return;
+    };
+    if stages.is_empty() {
+        report.add("", None, Some("stages"), "must contain at least one stage".to_string());
+        return;
+    }
+
+    let mut names = HashSet::new();
+    // Follow the field size from stage to stage to catch stages left without players
+    let mut entrants = match &config.groups {
+        Some(groups) if !stages[0].format.is_elimination() => groups.values().map(Vec::len).sum(),
+        _ => config.agents.len(),
+    };
+
+    for (index, stage) in stages.iter().enumerate() {
+        if stage.name.trim().is_empty() {
+            report.add("stages", Some(index), Some("name"), "must not be empty".to_string());
+        } else if !names.insert(stage.name.as_str()) {
+            report.add("stages", Some(index), Some("name"), format!("'{}' is used by more than one stage", stage.name));
+        }
+        if stage.groups == 0 {
+            report.add("stages", Some(index), Some("groups"), "must be at least 1".to_string());
+        }
+        if stage.advance == Some(0) {
+            report.add("stages", Some(index), Some("advance"), "must be at least 1".to_string());
+        }
+        if stage.best_of == Some(0) {
+            report.add("stages", Some(index), Some("best_of"), "must be at least 1".to_string());
+        }
+        if stage.rounds == Some(0) {
+            report.add("stages", Some(index), Some("rounds"), "must be at least 1".to_string());
+        }
+        if stage.rounds.is_some() && stage.format != StageFormat::Swiss {
+            report.add("stages", Some(index), Some("rounds"), "only applies to swiss stages".to_string());
+        }
+        if stage.best_of.is_some() && !stage.format.is_elimination() {
+            report.add("stages", Some(index), Some("best_of"), "only applies to elimination stages".to_string());
+        }
+        let grouped = stage.format.is_grouped();
+        for (key, value) in [("avoid_rematches", stage.avoid_rematches), ("separate_winners", stage.separate_winners)] {
+            if value.is_none() {
+                continue;
+            }
+            if !grouped {
+                report.add("stages", Some(index), Some(key), "only applies to round_robin and swiss stages".to_string());
+            } else if index == 0 {
+                report.add("stages", Some(index), Some(key), "does not apply to the first stage, which has no earlier games".to_string());
+            }
+        }
+
+        if entrants < 2 {
+            report.add("stages", Some(index), None, format!(
+                "'{}' would start with only {} player(s); raise `advance` in the stage before it", stage.name, entrants
+            ));
+            return;
+        }
+
+        entrants = match stage.format {
+            StageFormat::RoundRobin | StageFormat::Swiss => {
+                let num_groups = if index == 0 && config.groups.is_some() {
+                    config.groups.as_ref().map_or(1, BTreeMap::len)
+                } else {
+                    stage.groups.clamp(1, entrants)
+                };
+                // Groups are dealt as evenly as possible, see stage::distribute
+                (0..num_groups)
+                    .map(|group| entrants / num_groups + usize::from(group < entrants % num_groups))
+                    .map(|size| size.min(stage.advance()))
+                    .sum()
+            }
+            StageFormat::Placement | StageFormat::SingleElimination | StageFormat::DoubleElimination => {
+                entrants.min(stage.advance())
+            }
+        };
+    }
+}
+
+/// Read `path` and validate it, listing every problem in the error.
+pub fn load(path: &str, check_executables: bool) -> Result<TournamentConfig, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read config file {}: {}", path, e))?;
+
+    validate(&text, check_executables).map_err(|problems| {
+        let lines: Vec<String> = problems.iter().map(|problem| format!("  {}", problem)).collect();
+        format!("{} problem(s) in {}:\n{}", problems.len(), path, lines.join("\n")).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGENTS: &str = "[agents]\nteam1 = \"python3 a.py\"\nteam2 = \"python3 b.py\"\nteam3 = \"python3 c.py\"\n";
+
+    fn problems(text: &str) -> Vec<String> {
+        validate(text, false).err().unwrap_or_default().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        assert!(problems(&format!("game = \"tictactoe\"\n{}", AGENTS)).is_empty());
+    }
+
+    #[test]
+    fn reports_every_problem_with_key_and_line() {
+        let text = format!(
+            "game = \"chess\"\n[settings]\ntimeout = 0\ntimout = 5\n{}[groups]\n\"Group A\" = [\"team1\", \"team9\", \"team1\"]\n",
+            AGENTS
+        );
+        assert_eq!(problems(&text), vec![
+            "line 4: settings.timout: unknown setting (expected one of: timeout, visual, random_assignment, debug, port, port_range, seed, parallelism, engine, tiebreaks, playoff_games)",
+            "line 1: game: unsupported game type 'chess' (expected tictactoe or laskermorris)",
+            "line 10: groups.\"Group A\": unknown player 'team9' (not listed under [agents])",
+            "line 10: groups.\"Group A\": 'team1' is listed twice",
+            "line 9: groups: agent 'team2' is not assigned to any group",
+            "line 9: groups: agent 'team3' is not assigned to any group",
+            "line 3: settings.timeout: must be at least 1 second, got 0",
+        ]);
+    }
+
+    #[test]
+    fn stage_keys_point_at_the_right_stage() {
+        let text = format!(
+            "game = \"tictactoe\"\n{}[[stages]]\nname = \"A\"\nadvance = 1\n[[stages]]\nname = \"B\"\nbest_of = 3\n",
+            AGENTS
+        );
+        assert_eq!(problems(&text), vec![
+            "line 11: stages[1].best_of: only applies to elimination stages",
+            "line 9: stages[1]: 'B' would start with only 1 player(s); raise `advance` in the stage before it",
+        ]);
+    }
+
+    #[test]
+    fn unset_variables_are_reported_at_their_key() {
+        let text = "game = \"tictactoe\"\n[agents]\nteam1 = \"python3 ${TOURNAMENT_TEST_UNSET}/a.py\"\nteam2 = \"python3 ${TOURNAMENT_TEST_UNSET:-subs}/b.py\"\n";
+        let problems = problems(text);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("line 3: agents.team1: environment variable TOURNAMENT_TEST_UNSET is not set"), "{}", problems[0]);
+        assert_eq!(validate(&text.replace("${TOURNAMENT_TEST_UNSET}", "x"), false).unwrap().agents["team2"], "python3 subs/b.py");
+    }
+
+    #[test]
+    fn native_engine_limits() {
+        assert!(problems(&format!("game = \"tictactoe\"\n[settings]\nengine = \"native\"\n{}", AGENTS)).is_empty());
+        assert_eq!(problems(&format!("game = \"laskermorris\"\n[settings]\nengine = \"native\"\nvisual = true\n{}", AGENTS)), vec![
+            "line 3: settings.engine: the native engine cannot show games; set visual = false or use the referee",
+        ]);
+    }
+
+    #[test]
+    fn tiebreaks_are_checked() {
+        let settings = "[settings]\ntiebreaks = [\"wins\", \"head_to_head\", \"wins\"]\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", settings, AGENTS)), vec![
+            "line 3: settings.tiebreaks: 'wins' is listed twice",
+        ]);
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n[settings]\nplayoff_games = 2\n{}", AGENTS)), vec![
+            "line 3: settings.playoff_games: has no effect unless \"playoff\" is listed in tiebreaks",
+        ]);
+        let settings = "[settings]\ntiebreaks = [\"head_to_head\", \"playoff\"]\nplayoff_games = 2\n";
+        assert!(problems(&format!("game = \"tictactoe\"\n{}{}", settings, AGENTS)).is_empty());
+        let problems = problems(&format!("game = \"tictactoe\"\n[settings]\ntiebreaks = [\"buchholz\"]\n{}", AGENTS));
+        assert!(problems[0].starts_with("line 3: (config): "), "{}", problems[0]);
+    }
+
+    #[test]
+    fn scoring_must_rank_wins_above_losses() {
+        let scoring = "[scoring]\nwin = 3\nforfeit = -1\n";
+        assert!(problems(&format!("game = \"tictactoe\"\n{}{}", scoring, AGENTS)).is_empty());
+        let scoring = "[scoring]\nwin = 1\ndraw = 2\nforfeit = 1\npoints = 2\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", scoring, AGENTS)), vec![
+            "line 6: scoring.points: unknown setting (expected one of: win, draw, loss, forfeit)",
+            "line 4: scoring.draw: a draw (2) must be worth between a loss (0) and a win (1)",
+            "line 5: scoring.forfeit: a forfeit (1) must not be worth more than a loss (0)",
+        ]);
+    }
+
+    #[test]
+    fn rating_options_fit_the_model() {
+        let ratings = "[ratings]\nk_factor = 0\ninitial_rating = 1200\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", ratings, AGENTS)), vec![
+            "line 3: ratings.k_factor: must be greater than 0, got 0",
+        ]);
+        let ratings = "[ratings]\nmodel = \"glicko2\"\nk_factor = 16\ntau = 0.3\ninitial_deviation = -1\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", ratings, AGENTS)), vec![
+            "line 4: ratings.k_factor: does not apply to the Glicko-2 model",
+            "line 6: ratings.initial_deviation: must be greater than 0, got -1",
+        ]);
+    }
+
+    #[test]
+    fn ladder_needs_a_path() {
+        let ladder = "[ladder]\npath = \"\"\nevent = \" \"\nseed_groups = true\n";
+        let groups = "[groups]\n\"Group A\" = [\"team1\", \"team2\", \"team3\"]\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}{}", ladder, groups, AGENTS)), vec![
+            "line 3: ladder.path: must not be empty",
+            "line 4: ladder.event: must not be empty",
+            "line 5: ladder.seed_groups: cannot seed from the ladder when [groups] are predefined",
+        ]);
+        assert!(problems(&format!("game = \"tictactoe\"\n[ladder]\npath = \"ladder.json\"\n{}", AGENTS)).is_empty());
+    }
+
+    #[test]
+    fn seeds_are_unique_and_known() {
+        let seeds = "[seeds]\nteam1 = 1\nteam2 = 0\nteam3 = 1\nteam9 = 2\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", seeds, AGENTS)), vec![
+            "line 4: seeds.team2: must be at least 1",
+            "line 5: seeds.team3: seed 1 is already given to team1",
+            "line 6: seeds.team9: unknown player 'team9' (not listed under [agents])",
+        ]);
+        assert!(problems(&format!("game = \"tictactoe\"\n[seeds]\nteam2 = 1\n{}", AGENTS)).is_empty());
+    }
+
+    #[test]
+    fn regrouping_options_need_an_earlier_group_stage() {
+        let stages = "[[stages]]\nname = \"One\"\nseparate_winners = true\n\
+                      [[stages]]\nname = \"Two\"\navoid_rematches = false\nseparate_winners = true\n\
+                      [[stages]]\nname = \"Three\"\nformat = \"placement\"\navoid_rematches = true\n";
+        assert_eq!(problems(&format!("game = \"tictactoe\"\n{}{}", AGENTS, stages)), vec![
+            "line 8: stages[0].separate_winners: does not apply to the first stage, which has no earlier games",
+            "line 16: stages[2].avoid_rematches: only applies to round_robin and swiss stages",
+        ]);
+    }
+
+    #[test]
+    fn agent_programs_are_found_as_they_will_be_started() {
+        let dir = env::temp_dir().join(format!("validate_test_{}/my bots", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("run.sh");
+        fs::write(&program, "").unwrap();
+        let text = format!(
+            "game = \"tictactoe\"\n[agents]\nteam1 = \"'{}' --depth 3\"\nteam2 = \"'{}' --depth 3\"\nteam3 = \"'{}\"\n",
+            program.display(), dir.join("gone.sh").display(), program.display()
+        );
+        let problems: Vec<String> = validate(&text, true).err().unwrap_or_default().iter().map(ToString::to_string).collect();
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+        assert_eq!(problems, vec![
+            format!("line 4: agents.team2: '{}' was not found (use --skip-validation to start anyway)", dir.join("gone.sh").display()),
+            "line 5: agents.team3: command has an unterminated quote".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn type_errors_carry_a_line() {
+        let problems = problems(&format!("game = \"tictactoe\"\n[settings]\ntimeout = \"soon\"\n{}", AGENTS));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("line 3: (config): "), "{}", problems[0]);
+    }
+}
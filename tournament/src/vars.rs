@@ -0,0 +1,74 @@
+/// Expand `${VAR}` and `${VAR:-default}` in `value`, looking variables up
+/// with `lookup`. As in the shell, the default is used when the variable is
+/// unset or empty. A `$` that does not start `${` is left alone.
+pub fn expand<F>(value: &str, lookup: F) -> Result<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}')
+            .ok_or_else(|| format!("unterminated '${{' in \"{}\"", value))?;
+        let body = &after[..end];
+
+        let (name, default) = match body.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (body, None),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("'${{{}}}' is not a valid variable reference", body));
+        }
+
+        match (lookup(name).filter(|value| !value.is_empty()), default) {
+            (Some(value), _) => expanded.push_str(&value),
+            (None, Some(default)) => expanded.push_str(default),
+            (None, None) => {
+                return Err(format!(
+                    "environment variable {} is not set (write ${{{}:-default}} to give a fallback)", name, name
+                ));
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// `expand` against the process environment.
+pub fn expand_env(value: &str) -> Result<String, String> {
+    expand(value, |name| std::env::var(name).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "DIR" => Some("/srv/submissions".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_variables_and_defaults() {
+        assert_eq!(expand("python3 ${DIR}/team1/player.py", lookup).unwrap(), "python3 /srv/submissions/team1/player.py");
+        assert_eq!(expand("${MISSING:-out}/results.csv", lookup).unwrap(), "out/results.csv");
+        assert_eq!(expand("${EMPTY:-fallback}", lookup).unwrap(), "fallback");
+        assert_eq!(expand("cost: $5", lookup).unwrap(), "cost: $5");
+    }
+
+    #[test]
+    fn unset_variable_is_an_error() {
+        let error = expand("${MISSING}/player.py", lookup).unwrap_err();
+        assert!(error.contains("MISSING is not set"), "{}", error);
+        assert!(expand("${DIR", lookup).is_err());
+        assert!(expand("${a b}", lookup).is_err());
+    }
+}